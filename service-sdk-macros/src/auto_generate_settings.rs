@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Ident, Path};
+
+use crate::sdk_attributes::{combine_error, get_named_fields, SdkModelAttributes, SDK_ATTR};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SdkSetting {
+    Seq,
+    Postgres,
+    NoSqlWriter,
+    NoSqlReader,
+    ServiceBus,
+    LogLevel,
+    ProcessLogLevels,
+    LogOutput,
+}
+
+impl SdkSetting {
+    const ALL: [SdkSetting; 8] = [
+        SdkSetting::Seq,
+        SdkSetting::Postgres,
+        SdkSetting::NoSqlWriter,
+        SdkSetting::NoSqlReader,
+        SdkSetting::ServiceBus,
+        SdkSetting::LogLevel,
+        SdkSetting::ProcessLogLevels,
+        SdkSetting::LogOutput,
+    ];
+
+    fn from_attr(path: &Path) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|itm| path.is_ident(itm.get_attr_name()))
+    }
+
+    fn get_attr_name(&self) -> &'static str {
+        match self {
+            SdkSetting::Seq => "seq",
+            SdkSetting::Postgres => "postgres",
+            SdkSetting::NoSqlWriter => "no_sql_writer",
+            SdkSetting::NoSqlReader => "no_sql_reader",
+            SdkSetting::ServiceBus => "service_bus",
+            SdkSetting::LogLevel => "log_level",
+            SdkSetting::ProcessLogLevels => "process_log_levels",
+            SdkSetting::LogOutput => "log_output",
+        }
+    }
+
+    // Field which is used if none is marked with the attribute
+    fn get_default_field_name(&self) -> &'static str {
+        match self {
+            SdkSetting::Seq => "seq_conn_string",
+            SdkSetting::Postgres => "postgres_conn_string",
+            SdkSetting::NoSqlWriter => "my_no_sql_writer",
+            SdkSetting::NoSqlReader => "my_no_sql_tcp_reader",
+            SdkSetting::ServiceBus => "my_sb_tcp_host_port",
+            SdkSetting::LogLevel => "log_level",
+            SdkSetting::ProcessLogLevels => "process_log_levels",
+            SdkSetting::LogOutput => "log_output",
+        }
+    }
+
+    fn get_description(&self) -> &'static str {
+        match self {
+            SdkSetting::Seq => "Seq connection string",
+            SdkSetting::Postgres => "Postgres connection string",
+            SdkSetting::NoSqlWriter => "MyNoSql writer url",
+            SdkSetting::NoSqlReader => "MyNoSql tcp reader host:port",
+            SdkSetting::ServiceBus => "MyServiceBus tcp host:port",
+            SdkSetting::LogLevel => "Log level",
+            SdkSetting::ProcessLogLevels => "Process log levels",
+            SdkSetting::LogOutput => "Log output",
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        match self {
+            SdkSetting::Seq => cfg!(feature = "seq"),
+            SdkSetting::Postgres => cfg!(feature = "postgres"),
+            SdkSetting::NoSqlWriter => cfg!(feature = "no-sql-writer"),
+            SdkSetting::NoSqlReader => cfg!(feature = "no-sql-reader"),
+            SdkSetting::ServiceBus => cfg!(feature = "my-service-bus"),
+            SdkSetting::LogLevel | SdkSetting::ProcessLogLevels | SdkSetting::LogOutput => true,
+        }
+    }
+
+    // Log settings are optional, ServiceInfo methods of missing fields return None
+    fn is_log_setting(&self) -> bool {
+        matches!(
+            self,
+            SdkSetting::LogLevel | SdkSetting::ProcessLogLevels | SdkSetting::LogOutput
+        )
+    }
+
+    // Log settings are methods of ServiceInfo impl, their fields can be String or Option<String>
+    fn generate_impl(&self, reader: &Path, field: &Ident) -> TokenStream {
+        match self {
+            SdkSetting::Seq => quote::quote! {
+                #[async_trait]
+                impl SeqSettings for #reader {
+                    async fn get_conn_string(&self) -> String {
+                        let read_access = self.settings.read().await;
+                        read_access.#field.clone()
+                    }
+                }
+            },
+            SdkSetting::Postgres => quote::quote! {
+                #[async_trait]
+                impl PostgresSettings for #reader {
+                    async fn get_connection_string(&self) -> String {
+                        let read_access = self.settings.read().await;
+                        read_access.#field.clone()
+                    }
+                }
+            },
+            SdkSetting::NoSqlWriter => quote::quote! {
+                #[async_trait]
+                impl MyNoSqlWriterSettings for #reader {
+                    async fn get_url(&self) -> String {
+                        let read_access = self.settings.read().await;
+                        read_access.#field.clone()
+                    }
+                }
+            },
+            SdkSetting::NoSqlReader => quote::quote! {
+                #[async_trait]
+                impl service_sdk::my_no_sql_sdk::reader::MyNoSqlTcpConnectionSettings for #reader {
+                    async fn get_host_port(&self) -> String {
+                        let read_access = self.settings.read().await;
+                        read_access.#field.clone()
+                    }
+                }
+            },
+            SdkSetting::ServiceBus => quote::quote! {
+                #[async_trait::async_trait]
+                impl MyServiceBusSettings for #reader {
+                    async fn get_host_port(&self) -> String {
+                        let read_access = self.settings.read().await;
+                        return read_access.#field.clone();
+                    }
+                }
+            },
+            SdkSetting::LogLevel => quote::quote! {
+                async fn get_log_level(&self) -> Option<String> {
+                    let read_access = self.settings.read().await;
+                    read_access.#field.clone().into()
+                }
+            },
+            SdkSetting::ProcessLogLevels => quote::quote! {
+                async fn get_process_log_levels(&self) -> Option<String> {
+                    let read_access = self.settings.read().await;
+                    read_access.#field.clone().into()
+                }
+            },
+            SdkSetting::LogOutput => quote::quote! {
+                async fn get_log_output(&self) -> Option<String> {
+                    let read_access = self.settings.read().await;
+                    read_access.#field.clone().into()
+                }
+            },
+        }
+    }
+}
+
+// #[sdk(reader = MyReader)] on the settings model and #[sdk(seq)], #[sdk(service_bus)], ... on its fields
+pub fn generate(input: &DeriveInput) -> Result<TokenStream, syn::Error> {
+    let reader = SdkModelAttributes::parse(input)?.get_reader(input);
+    let fields = get_settings_fields(input)?;
+
+    let mut errors: Option<syn::Error> = None;
+    let mut auto_generates = Vec::new();
+
+    for setting in SdkSetting::ALL {
+        if !setting.is_enabled() || setting.is_log_setting() {
+            continue;
+        }
+
+        match fields.get(&setting) {
+            Some(field) => auto_generates.push(setting.generate_impl(&reader, field)),
+            None => {
+                let err = syn::Error::new(
+                    input.ident.span(),
+                    format!(
+                        "AutoGenerateSettingsTraits: {} field is missing. Add `{}: String` field or mark a field with #[{}({})]",
+                        setting.get_description(),
+                        setting.get_default_field_name(),
+                        SDK_ATTR,
+                        setting.get_attr_name(),
+                    ),
+                );
+                combine_error(&mut errors, err);
+            }
+        }
+    }
+
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    Ok(quote::quote! {
+        #(#auto_generates)*
+    })
+}
+
+// Used by SdkSettingsTraits, so readers without AutoGenerateSettingsTraits get the log settings too.
+// Attribute errors are reported by AutoGenerateSettingsTraits, here the fields are just not found
+pub fn generate_log_settings_methods(input: &DeriveInput, reader: &Path) -> Vec<TokenStream> {
+    let fields = get_settings_fields(input).unwrap_or_default();
+
+    SdkSetting::ALL
+        .into_iter()
+        .filter(|itm| itm.is_log_setting())
+        .filter_map(|itm| {
+            fields
+                .get(&itm)
+                .map(|field| itm.generate_impl(reader, field))
+        })
+        .collect()
+}
+
+fn get_settings_fields(input: &DeriveInput) -> Result<HashMap<SdkSetting, Ident>, syn::Error> {
+    let fields = get_named_fields(input, "AutoGenerateSettingsTraits")?;
+
+    let mut errors: Option<syn::Error> = None;
+    let mut marked: HashMap<SdkSetting, Ident> = HashMap::new();
+
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|itm| itm.path().is_ident(SDK_ATTR))
+        {
+            let result = attr.parse_nested_meta(|meta| {
+                let Some(setting) = SdkSetting::from_attr(&meta.path) else {
+                    return Err(meta.error(
+                        "unknown sdk attribute. Expected one of: seq, postgres, no_sql_writer, no_sql_reader, service_bus, log_level, process_log_levels, log_output",
+                    ));
+                };
+
+                if let Some(other_field) = marked.get(&setting) {
+                    return Err(meta.error(format!(
+                        "#[{}({})] is already set on field `{}`",
+                        SDK_ATTR,
+                        setting.get_attr_name(),
+                        other_field
+                    )));
+                }
+
+                marked.insert(setting, field_name.clone());
+                Ok(())
+            });
+
+            if let Err(err) = result {
+                combine_error(&mut errors, err);
+            }
+        }
+    }
+
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    // Models written before the attributes appeared rely on the field names
+    for setting in SdkSetting::ALL {
+        if marked.contains_key(&setting) {
+            continue;
+        }
+
+        let default_field = fields
+            .iter()
+            .filter_map(|itm| itm.ident.as_ref())
+            .find(|itm| *itm == setting.get_default_field_name());
+
+        if let Some(default_field) = default_field {
+            marked.insert(setting, default_field.clone());
+        }
+    }
+
+    Ok(marked)
+}
@@ -0,0 +1,105 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Nothing is executed when the macro is expanded. GIT_SHA and RUSTC_VERSION are set by build.rs
+// of the service (cargo:rustc-env=GIT_SHA=...), which reruns on .git/HEAD change.
+// BUILD_TIMESTAMP can be set the same way, otherwise the time the crate is compiled is used
+pub fn generate() -> proc_macro2::TokenStream {
+    let build_timestamp = get_build_timestamp();
+
+    quote::quote! {
+        service_sdk::BuildInfo {
+            git_sha: match option_env!("GIT_SHA") {
+                Some(value) => value,
+                None => service_sdk::UNKNOWN_BUILD_INFO_VALUE,
+            },
+            build_timestamp: match option_env!("BUILD_TIMESTAMP") {
+                Some(value) => value,
+                None => #build_timestamp,
+            },
+            rustc: match option_env!("RUSTC_VERSION") {
+                Some(value) => value,
+                None => service_sdk::UNKNOWN_BUILD_INFO_VALUE,
+            },
+        }
+    }
+}
+
+// SOURCE_DATE_EPOCH is respected for reproducible builds
+fn get_build_timestamp() -> String {
+    let seconds = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(value) => value.parse::<u64>().unwrap_or(0),
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|itm| itm.as_secs())
+            .unwrap_or(0),
+    };
+
+    format_timestamp(seconds)
+}
+
+fn format_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let seconds_of_day = seconds % 86400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// Days since 1970-01-01 to (year, month, day). http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, format_timestamp};
+
+    #[test]
+    fn test_epoch() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_leap_years() {
+        // 2000 is a leap year, 1900 and 2100 are not
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-25508), (1900, 3, 1));
+        assert_eq!(civil_from_days(47541), (2100, 3, 1));
+        assert_eq!(civil_from_days(47540), (2100, 2, 28));
+    }
+
+    #[test]
+    fn test_year_boundaries() {
+        assert_eq!(civil_from_days(10956), (1999, 12, 31));
+        assert_eq!(civil_from_days(10957), (2000, 1, 1));
+        assert_eq!(civil_from_days(19722), (2023, 12, 31));
+        assert_eq!(civil_from_days(19723), (2024, 1, 1));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1709251199), "2024-02-29T23:59:59Z");
+        assert_eq!(format_timestamp(1709251200), "2024-03-01T00:00:00Z");
+    }
+}
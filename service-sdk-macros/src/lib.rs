@@ -1,10 +1,17 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 
+mod auto_generate_settings;
+mod build_info;
+mod sdk_attributes;
+mod sdk_settings_traits;
+
 #[proc_macro]
 pub fn generate_settings_signature(_item: TokenStream) -> TokenStream {
-    let mut traits = vec![];
+    #[allow(unused_mut)]
+    let mut traits: Vec<proc_macro2::TokenStream> = vec![];
 
+    #[cfg(feature = "seq")]
     traits.push(quote::quote!(+ SeqSettings));
 
     #[cfg(feature = "my-service-bus")]
@@ -23,90 +30,24 @@ pub fn generate_settings_signature(_item: TokenStream) -> TokenStream {
     result.into()
 }
 
-#[proc_macro_derive(SdkSettingsTraits)]
-pub fn generate_sdk_settings_traits(_input: TokenStream) -> TokenStream {
-    quote::quote! {
-    #[async_trait]
-    impl service_sdk::ServiceInfo for SettingsReader {
-        fn get_service_name(&self) -> rust_extensions::StrOrString<'static> {
-            if let Ok(suffix) = std::env::var("SERVICE_NAME_SUFFIX") {
-                return format!("{}-{}", env!("CARGO_PKG_NAME"), suffix).into();
-            }else{
-                env!("CARGO_PKG_NAME").into()
-            }
+#[proc_macro_derive(SdkSettingsTraits, attributes(sdk))]
+pub fn generate_sdk_settings_traits(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
 
-        }
-        fn get_service_version(&self) -> rust_extensions::StrOrString<'static> {
-            env!("CARGO_PKG_VERSION").into()
-        }
+    match sdk_settings_traits::generate(&input) {
+        Ok(result) => result.into(),
+        Err(err) => err.to_compile_error().into(),
     }
-
-        }
-    .into()
 }
 
-#[proc_macro_derive(AutoGenerateSettingsTraits)]
-pub fn auto_generate_settings_traits(_input: TokenStream) -> TokenStream {
-    let mut auto_generates = Vec::new();
-
-    auto_generates.push(quote::quote! {
-        #[async_trait]
-        impl SeqSettings for SettingsReader {
-           async fn get_conn_string(&self) -> String {
-            let read_access = self.settings.read().await;
-            read_access.seq_conn_string.clone()
-        }
-    }
-    });
-
-    #[cfg(feature = "postgres")]
-    auto_generates.push(quote::quote! {
-        #[async_trait]
-        impl PostgresSettings for SettingsReader {
-            async fn get_connection_string(&self) -> String {
-                let read_access = self.settings.read().await;
-                read_access.postgres_conn_string.clone()
-            }
-        }
-    });
+#[proc_macro_derive(AutoGenerateSettingsTraits, attributes(sdk))]
+pub fn auto_generate_settings_traits(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
 
-    #[cfg(feature = "no-sql-writer")]
-    auto_generates.push(quote::quote! {
-            #[async_trait]
-    impl MyNoSqlWriterSettings for SettingsReader {
-        async fn get_url(&self) -> String {
-            let read_access = self.settings.read().await;
-            read_access.my_no_sql_writer.clone()
-        }
+    match auto_generate_settings::generate(&input) {
+        Ok(result) => result.into(),
+        Err(err) => err.to_compile_error().into(),
     }
-        });
-
-    #[cfg(feature = "no-sql-reader")]
-    auto_generates.push(quote::quote!(
-        #[async_trait]
-        impl service_sdk::my_no_sql_sdk::reader::MyNoSqlTcpConnectionSettings for SettingsReader {
-            async fn get_host_port(&self) -> String {
-                let read_access = self.settings.read().await;
-                read_access.my_no_sql_tcp_reader.clone()
-            }
-        }
-    ));
-
-    #[cfg(feature = "my-service-bus")]
-    auto_generates.push(quote::quote!(
-        #[async_trait::async_trait]
-        impl MyServiceBusSettings for SettingsReader {
-            async fn get_host_port(&self) -> String {
-                let read_access = self.settings.read().await;
-                return read_access.my_sb_tcp_host_port.clone();
-            }
-        }
-    ));
-
-    quote::quote! {
-    #(#auto_generates)*
-    }
-    .into()
 }
 
 #[proc_macro]
@@ -142,6 +83,11 @@ pub fn use_grpc_server(_input: TokenStream) -> TokenStream {
 pub fn use_settings(_input: TokenStream) -> TokenStream {
     let mut uses = vec![];
 
+    #[cfg(feature = "seq")]
+    uses.push(quote::quote!(
+        use service_sdk::my_logger::my_seq_logger::SeqSettings;
+    ));
+
     uses.push(quote::quote!(
         use service_sdk::flurl;
     ));
@@ -177,7 +123,6 @@ pub fn use_settings(_input: TokenStream) -> TokenStream {
         use service_sdk::my_settings_reader;
         use service_sdk::macros::SdkSettingsTraits;
         use service_sdk::rust_extensions;
-        use service_sdk::my_logger::my_seq_logger::SeqSettings;
         use service_sdk::macros::AutoGenerateSettingsTraits;
         #(#uses)*
     }
@@ -299,3 +244,9 @@ pub fn generate_grpc_service(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+// Captures git SHA, build timestamp and rustc version of the service at compile time
+#[proc_macro]
+pub fn build_info(_input: TokenStream) -> TokenStream {
+    build_info::generate().into()
+}
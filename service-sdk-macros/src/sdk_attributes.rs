@@ -0,0 +1,125 @@
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, token::Comma, Data, DeriveInput, Expr, Field, Fields,
+    Ident, LitStr, Path,
+};
+
+pub const SDK_ATTR: &str = "sdk";
+// Type generated by my_settings_reader::SettingsModel derive
+const DEFAULT_READER_TYPE: &str = "SettingsReader";
+pub const DEFAULT_NAME_SUFFIX_ENV: &str = "SERVICE_NAME_SUFFIX";
+
+pub enum NameSuffixSource {
+    Env(LitStr),
+    SettingsField(Ident),
+}
+
+// #[sdk(...)] on the settings model. Shared by AutoGenerateSettingsTraits and SdkSettingsTraits
+pub struct SdkModelAttributes {
+    pub reader: Option<Path>,
+    pub service_name: Option<Expr>,
+    pub name_suffix: Option<NameSuffixSource>,
+    pub version: Option<Expr>,
+}
+
+impl SdkModelAttributes {
+    pub fn parse(input: &DeriveInput) -> Result<Self, syn::Error> {
+        let mut result = Self {
+            reader: None,
+            service_name: None,
+            name_suffix: None,
+            version: None,
+        };
+
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|itm| itm.path().is_ident(SDK_ATTR))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("reader") {
+                    if result.reader.is_some() {
+                        return Err(meta.error("reader is already set"));
+                    }
+
+                    result.reader = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("service_name") {
+                    if result.service_name.is_some() {
+                        return Err(meta.error("service_name is already set"));
+                    }
+
+                    result.service_name = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("version") {
+                    if result.version.is_some() {
+                        return Err(meta.error("version is already set"));
+                    }
+
+                    result.version = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("name_suffix_env") || meta.path.is_ident("name_suffix_field")
+                {
+                    if result.name_suffix.is_some() {
+                        return Err(meta.error(
+                            "name suffix source is already set. Use either name_suffix_env or name_suffix_field",
+                        ));
+                    }
+
+                    let value = meta.value()?;
+
+                    result.name_suffix = if meta.path.is_ident("name_suffix_env") {
+                        Some(NameSuffixSource::Env(value.parse()?))
+                    } else {
+                        Some(NameSuffixSource::SettingsField(value.parse()?))
+                    };
+
+                    return Ok(());
+                }
+
+                Err(meta.error(
+                    "unknown sdk attribute. Expected one of: reader, service_name, name_suffix_env, name_suffix_field, version",
+                ))
+            })?;
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_reader(&self, input: &DeriveInput) -> Path {
+        match &self.reader {
+            Some(reader) => reader.clone(),
+            None => Ident::new(DEFAULT_READER_TYPE, input.ident.span()).into(),
+        }
+    }
+}
+
+pub fn get_named_fields<'s>(
+    input: &'s DeriveInput,
+    derive_name: &str,
+) -> Result<&'s Punctuated<Field, Comma>, syn::Error> {
+    let error = format!(
+        "{} can be derived only for a struct with named fields",
+        derive_name
+    );
+
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(syn::Error::new(data.fields.span(), error)),
+        },
+        _ => Err(syn::Error::new(input.ident.span(), error)),
+    }
+}
+
+pub fn combine_error(errors: &mut Option<syn::Error>, err: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(err),
+        None => *errors = Some(err),
+    }
+}
@@ -0,0 +1,88 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, LitStr};
+
+use crate::sdk_attributes::{
+    get_named_fields, NameSuffixSource, SdkModelAttributes, DEFAULT_NAME_SUFFIX_ENV,
+};
+
+// ServiceInfo for the reader of the settings model. Name and version come from the Cargo.toml
+// of the service unless they are set with #[sdk(service_name = ..., version = ...)].
+// Log settings are read from log_level, process_log_levels and log_output fields if the model has them
+pub fn generate(input: &DeriveInput) -> Result<TokenStream, syn::Error> {
+    let attributes = SdkModelAttributes::parse(input)?;
+    let reader = attributes.get_reader(input);
+
+    let service_name = match &attributes.service_name {
+        Some(service_name) => quote::quote!((#service_name)),
+        None => quote::quote!(env!("CARGO_PKG_NAME")),
+    };
+
+    let service_version = match &attributes.version {
+        Some(version) => quote::quote!((#version)),
+        None => quote::quote!(env!("CARGO_PKG_VERSION")),
+    };
+
+    let name_suffix = match &attributes.name_suffix {
+        Some(NameSuffixSource::Env(env_name)) => generate_env_suffix(&service_name, env_name),
+        Some(NameSuffixSource::SettingsField(field)) => {
+            let fields = get_named_fields(input, "SdkSettingsTraits")?;
+
+            if !fields.iter().any(|itm| itm.ident.as_ref() == Some(field)) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!(
+                        "settings field `{}` is not found in `{}`",
+                        field, input.ident
+                    ),
+                ));
+            }
+
+            quote::quote! {
+                // Read once, the name does not change on settings reload. If settings are being
+                // reloaded, the name is returned without suffix and the suffix is read on the next call
+                static NAME_SUFFIX: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+                let suffix = NAME_SUFFIX.get().or_else(|| {
+                    let read_access = self.settings.try_read().ok()?;
+                    Some(NAME_SUFFIX.get_or_init(|| read_access.#field.clone()))
+                });
+
+                if let Some(suffix) = suffix.filter(|itm| !itm.is_empty()) {
+                    return format!("{}-{}", #service_name, suffix).into();
+                }
+            }
+        }
+        None => generate_env_suffix(
+            &service_name,
+            &LitStr::new(DEFAULT_NAME_SUFFIX_ENV, input.ident.span()),
+        ),
+    };
+
+    let log_settings_methods =
+        crate::auto_generate_settings::generate_log_settings_methods(input, &reader);
+
+    Ok(quote::quote! {
+        #[async_trait::async_trait]
+        impl service_sdk::ServiceInfo for #reader {
+            fn get_service_name(&self) -> rust_extensions::StrOrString<'static> {
+                #name_suffix
+
+                #service_name.into()
+            }
+
+            fn get_service_version(&self) -> rust_extensions::StrOrString<'static> {
+                #service_version.into()
+            }
+
+            #(#log_settings_methods)*
+        }
+    })
+}
+
+fn generate_env_suffix(service_name: &TokenStream, env_name: &LitStr) -> TokenStream {
+    quote::quote! {
+        if let Ok(suffix) = std::env::var(#env_name) {
+            return format!("{}-{}", #service_name, suffix).into();
+        }
+    }
+}
@@ -0,0 +1,20 @@
+#[test]
+fn sdk_settings_traits() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+
+    // Required fields are checked only for the enabled features
+    #[cfg(feature = "seq")]
+    t.compile_fail("tests/ui/fail/seq/*.rs");
+
+    // Other features would require their fields as well
+    #[cfg(all(
+        feature = "seq",
+        feature = "my-service-bus",
+        not(feature = "postgres"),
+        not(feature = "no-sql-reader"),
+        not(feature = "no-sql-writer")
+    ))]
+    t.pass("tests/ui/pass/seq_service_bus/*.rs");
+}
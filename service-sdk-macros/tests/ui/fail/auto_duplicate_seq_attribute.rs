@@ -0,0 +1,11 @@
+use service_sdk_macros::AutoGenerateSettingsTraits;
+
+#[derive(AutoGenerateSettingsTraits)]
+pub struct SettingsModel {
+    #[sdk(seq)]
+    pub seq: String,
+    #[sdk(seq)]
+    pub seq_conn_string: String,
+}
+
+fn main() {}
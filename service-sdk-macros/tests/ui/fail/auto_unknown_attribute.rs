@@ -0,0 +1,9 @@
+use service_sdk_macros::AutoGenerateSettingsTraits;
+
+#[derive(AutoGenerateSettingsTraits)]
+pub struct SettingsModel {
+    #[sdk(sequence)]
+    pub seq: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+#[sdk(name_suffix_field = env_name)]
+pub struct SettingsModel {
+    pub seq_conn_string: String,
+}
+
+fn main() {}
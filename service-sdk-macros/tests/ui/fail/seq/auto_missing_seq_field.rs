@@ -0,0 +1,13 @@
+use service_sdk_macros::AutoGenerateSettingsTraits;
+
+// Fields of the other features are there, so the error is the same for any set of features
+#[derive(AutoGenerateSettingsTraits)]
+pub struct SettingsModel {
+    pub seq: String,
+    pub postgres_conn_string: String,
+    pub my_no_sql_writer: String,
+    pub my_no_sql_tcp_reader: String,
+    pub my_sb_tcp_host_port: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+#[sdk(name_suffix_env = "ENV_NAME", name_suffix_field = env_name)]
+pub struct SettingsModel {
+    pub env_name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+#[sdk(service_title = "payments")]
+pub struct SettingsModel {
+    pub seq_conn_string: String,
+}
+
+fn main() {}
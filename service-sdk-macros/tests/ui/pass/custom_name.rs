@@ -0,0 +1,28 @@
+#[path = "../stubs/rust_extensions.rs"]
+mod rust_extensions;
+#[path = "../stubs/service_sdk.rs"]
+mod service_sdk;
+
+use service_sdk::ServiceInfo;
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+#[sdk(reader = PaymentsReader)]
+#[sdk(service_name = "payments", version = "2.1.0", name_suffix_env = "PAYMENTS_ENV")]
+pub struct PaymentsSettings {
+    pub seq_conn_string: String,
+}
+
+pub struct PaymentsReader;
+
+fn main() {
+    let reader = PaymentsReader;
+
+    std::env::set_var("SERVICE_NAME_SUFFIX", "ignored");
+    std::env::remove_var("PAYMENTS_ENV");
+    assert_eq!(reader.get_service_name().as_str(), "payments");
+    assert_eq!(reader.get_service_version().as_str(), "2.1.0");
+
+    std::env::set_var("PAYMENTS_ENV", "prod");
+    assert_eq!(reader.get_service_name().as_str(), "payments-prod");
+}
@@ -0,0 +1,28 @@
+#[path = "../stubs/rust_extensions.rs"]
+mod rust_extensions;
+#[path = "../stubs/service_sdk.rs"]
+mod service_sdk;
+
+use service_sdk::ServiceInfo;
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+pub struct SettingsModel {
+    pub seq_conn_string: String,
+}
+
+pub struct SettingsReader;
+
+fn main() {
+    let reader = SettingsReader;
+
+    std::env::remove_var("SERVICE_NAME_SUFFIX");
+    assert_eq!(reader.get_service_name().as_str(), env!("CARGO_PKG_NAME"));
+    assert_eq!(reader.get_service_version().as_str(), env!("CARGO_PKG_VERSION"));
+
+    std::env::set_var("SERVICE_NAME_SUFFIX", "uat");
+    assert_eq!(
+        reader.get_service_name().as_str(),
+        format!("{}-uat", env!("CARGO_PKG_NAME"))
+    );
+}
@@ -0,0 +1,37 @@
+#[path = "../stubs/rust_extensions.rs"]
+mod rust_extensions;
+#[path = "../stubs/service_sdk.rs"]
+mod service_sdk;
+
+use service_sdk::ServiceInfo;
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+pub struct SettingsModel {
+    #[sdk(log_level)]
+    pub level: Option<String>,
+    pub process_log_levels: String,
+}
+
+pub struct SettingsReader {
+    pub settings: tokio::sync::RwLock<SettingsModel>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let reader = SettingsReader {
+        settings: tokio::sync::RwLock::new(SettingsModel {
+            level: Some("debug".to_string()),
+            process_log_levels: "Orders=warning".to_string(),
+        }),
+    };
+
+    assert_eq!(reader.get_log_level().await.as_deref(), Some("debug"));
+    assert_eq!(
+        reader.get_process_log_levels().await.as_deref(),
+        Some("Orders=warning")
+    );
+
+    // Missing field keeps the default
+    assert_eq!(reader.get_log_output().await, None);
+}
@@ -0,0 +1,32 @@
+#[path = "../../stubs/settings_traits.rs"]
+mod settings_traits;
+
+use async_trait::async_trait;
+use service_sdk_macros::AutoGenerateSettingsTraits;
+use settings_traits::{MyServiceBusSettings, SeqSettings};
+
+#[derive(AutoGenerateSettingsTraits)]
+#[sdk(reader = PaymentsReader)]
+pub struct PaymentsSettings {
+    #[sdk(seq)]
+    pub seq: String,
+    #[sdk(service_bus)]
+    pub sb_tcp: String,
+}
+
+pub struct PaymentsReader {
+    pub settings: tokio::sync::RwLock<PaymentsSettings>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let reader = PaymentsReader {
+        settings: tokio::sync::RwLock::new(PaymentsSettings {
+            seq: "http://seq:5341".to_string(),
+            sb_tcp: "sb:6421".to_string(),
+        }),
+    };
+
+    assert_eq!(reader.get_conn_string().await, "http://seq:5341");
+    assert_eq!(reader.get_host_port().await, "sb:6421");
+}
@@ -0,0 +1,37 @@
+#[path = "../stubs/rust_extensions.rs"]
+mod rust_extensions;
+#[path = "../stubs/service_sdk.rs"]
+mod service_sdk;
+
+use service_sdk::ServiceInfo;
+use service_sdk_macros::SdkSettingsTraits;
+
+#[derive(SdkSettingsTraits)]
+#[sdk(service_name = "orders", name_suffix_field = env_name)]
+pub struct SettingsModel {
+    pub env_name: String,
+}
+
+pub struct SettingsReader {
+    pub settings: tokio::sync::RwLock<SettingsModel>,
+}
+
+fn main() {
+    let reader = SettingsReader {
+        settings: tokio::sync::RwLock::new(SettingsModel {
+            env_name: "stage".to_string(),
+        }),
+    };
+
+    // Settings are being reloaded, the name has no suffix until they are released
+    let write_access = reader.settings.try_write().unwrap();
+    assert_eq!(reader.get_service_name().as_str(), "orders");
+    drop(write_access);
+
+    assert_eq!(reader.get_service_name().as_str(), "orders-stage");
+    assert_eq!(reader.get_service_version().as_str(), env!("CARGO_PKG_VERSION"));
+
+    // Suffix is read once, reload does not change the name
+    reader.settings.try_write().unwrap().env_name = String::new();
+    assert_eq!(reader.get_service_name().as_str(), "orders-stage");
+}
@@ -0,0 +1,19 @@
+pub struct StrOrString<'s>(std::borrow::Cow<'s, str>);
+
+impl<'s> StrOrString<'s> {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&'static str> for StrOrString<'static> {
+    fn from(value: &'static str) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<String> for StrOrString<'static> {
+    fn from(value: String) -> Self {
+        Self(value.into())
+    }
+}
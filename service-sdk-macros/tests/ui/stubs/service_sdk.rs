@@ -0,0 +1,18 @@
+// Mirrors service_sdk::ServiceInfo, the macros crate can not depend on service-sdk
+#[async_trait::async_trait]
+pub trait ServiceInfo {
+    fn get_service_name(&self) -> crate::rust_extensions::StrOrString<'static>;
+    fn get_service_version(&self) -> crate::rust_extensions::StrOrString<'static>;
+
+    async fn get_log_level(&self) -> Option<String> {
+        None
+    }
+
+    async fn get_process_log_levels(&self) -> Option<String> {
+        None
+    }
+
+    async fn get_log_output(&self) -> Option<String> {
+        None
+    }
+}
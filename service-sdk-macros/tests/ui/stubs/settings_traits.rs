@@ -0,0 +1,10 @@
+// Mirrors SeqSettings and MyServiceBusSettings, which AutoGenerateSettingsTraits implements
+#[async_trait::async_trait]
+pub trait SeqSettings {
+    async fn get_conn_string(&self) -> String;
+}
+
+#[async_trait::async_trait]
+pub trait MyServiceBusSettings {
+    async fn get_host_port(&self) -> String;
+}
@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const REDACTED_VALUE: &str = "***";
+
+const DEFAULT_REDACTED_HEADERS: &[&str] = &["authorization", "cookie", "set-cookie", "x-api-key"];
+const DEFAULT_REDACTED_QUERY_PARAMS: &[&str] =
+    &["token", "access_token", "password", "secret", "api_key"];
+
+pub struct AccessLogConfig {
+    // Share of 2xx and 3xx requests to log, 0.0..=1.0
+    pub success_sample_rate: f64,
+    // Share of 4xx requests to log. 5xx are always logged
+    pub client_error_sample_rate: f64,
+    pub log_headers: bool,
+    pub redacted_headers: Vec<String>,
+    pub redacted_query_params: Vec<String>,
+}
+
+impl AccessLogConfig {
+    pub fn new() -> Self {
+        Self {
+            success_sample_rate: 1.0,
+            client_error_sample_rate: 1.0,
+            log_headers: false,
+            redacted_headers: DEFAULT_REDACTED_HEADERS
+                .iter()
+                .map(|itm| itm.to_string())
+                .collect(),
+            redacted_query_params: DEFAULT_REDACTED_QUERY_PARAMS
+                .iter()
+                .map(|itm| itm.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn set_success_sample_rate(&mut self, sample_rate: f64) -> &mut Self {
+        self.success_sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn set_client_error_sample_rate(&mut self, sample_rate: f64) -> &mut Self {
+        self.client_error_sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn log_headers(&mut self) -> &mut Self {
+        self.log_headers = true;
+        self
+    }
+
+    // Names are compared case insensitive
+    pub fn redact_header(&mut self, name: impl Into<String>) -> &mut Self {
+        self.redacted_headers.push(name.into().to_lowercase());
+        self
+    }
+
+    pub fn redact_query_param(&mut self, name: impl Into<String>) -> &mut Self {
+        self.redacted_query_params.push(name.into().to_lowercase());
+        self
+    }
+
+    pub fn is_header_redacted(&self, name: &str) -> bool {
+        self.redacted_headers
+            .iter()
+            .any(|itm| itm.eq_ignore_ascii_case(name))
+    }
+
+    // a=1&token=abc -> a=1&token=***
+    pub fn redact_query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if self.is_query_param_redacted(name) => {
+                    format!("{}={}", name, REDACTED_VALUE)
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    // Name is compared decoded, so acc%65ss_token is redacted as access_token
+    fn is_query_param_redacted(&self, name: &str) -> bool {
+        let name = name.replace('+', " ");
+        let name = percent_encoding::percent_decode_str(&name).decode_utf8_lossy();
+
+        self.redacted_query_params
+            .iter()
+            .any(|itm| itm.eq_ignore_ascii_case(&name))
+    }
+}
+
+// Takes exactly sample_rate share of the requests: with 0.1 every 10th one is logged
+pub struct AccessLogSampler {
+    counter: AtomicU64,
+}
+
+impl AccessLogSampler {
+    pub fn new() -> Self {
+        Self {
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_sampled(&self, sample_rate: f64) -> bool {
+        if sample_rate >= 1.0 {
+            return true;
+        }
+
+        if sample_rate <= 0.0 {
+            return false;
+        }
+
+        let no = self.counter.fetch_add(1, Ordering::Relaxed) as f64;
+        ((no + 1.0) * sample_rate).floor() > (no * sample_rate).floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessLogConfig, AccessLogSampler};
+
+    fn count_sampled(sample_rate: f64, requests: usize) -> usize {
+        let sampler = AccessLogSampler::new();
+
+        (0..requests)
+            .filter(|_| sampler.is_sampled(sample_rate))
+            .count()
+    }
+
+    #[test]
+    fn test_sampler_takes_exact_share() {
+        assert_eq!(count_sampled(1.0, 100), 100);
+        assert_eq!(count_sampled(0.0, 100), 0);
+        assert_eq!(count_sampled(0.1, 100), 10);
+        assert_eq!(count_sampled(0.25, 100), 25);
+        assert_eq!(count_sampled(0.5, 3), 1);
+    }
+
+    #[test]
+    fn test_sampler_spreads_sampled_requests() {
+        let sampler = AccessLogSampler::new();
+
+        let sampled: Vec<usize> = (0..30).filter(|_| sampler.is_sampled(0.1)).collect();
+
+        assert_eq!(sampled, vec![9, 19, 29]);
+    }
+
+    #[test]
+    fn test_sample_rates_are_clamped() {
+        let mut config = AccessLogConfig::new();
+        config
+            .set_success_sample_rate(2.0)
+            .set_client_error_sample_rate(-1.0);
+
+        assert_eq!(config.success_sample_rate, 1.0);
+        assert_eq!(config.client_error_sample_rate, 0.0);
+    }
+
+    #[test]
+    fn test_default_query_params_are_redacted() {
+        let config = AccessLogConfig::new();
+
+        assert_eq!(
+            config.redact_query("a=1&token=abc&PASSWORD=secret&b"),
+            "a=1&token=***&PASSWORD=***&b"
+        );
+    }
+
+    #[test]
+    fn test_encoded_names_are_redacted() {
+        let config = AccessLogConfig::new();
+
+        assert_eq!(
+            config.redact_query("acc%65ss_token=abc&api%5Fkey=123"),
+            "acc%65ss_token=***&api%5Fkey=***"
+        );
+    }
+
+    #[test]
+    fn test_custom_query_param_is_redacted() {
+        let mut config = AccessLogConfig::new();
+        config.redact_query_param("PIN");
+
+        assert_eq!(config.redact_query("pin=1234&id=5"), "pin=***&id=5");
+        assert_eq!(config.redact_query(""), "");
+    }
+
+    #[test]
+    fn test_headers_are_compared_case_insensitive() {
+        let mut config = AccessLogConfig::new();
+        config.redact_header("X-Session");
+
+        assert!(config.is_header_redacted("Authorization"));
+        assert!(config.is_header_redacted("x-session"));
+        assert!(!config.is_header_redacted("content-type"));
+    }
+}
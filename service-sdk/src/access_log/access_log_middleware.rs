@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpServerMiddleware, HttpServerRequestFlow,
+    RequestCredentials,
+};
+use my_logger::LogEventCtx;
+use stopwatch::Stopwatch;
+
+use crate::{
+    AccessLogConfig, AccessLogSampler, HttpMetricsPaths, LogLevel, LOG_LEVEL_FILTER,
+    REQUEST_ID_LOG_KEY, UNMATCHED_PATH_LABEL,
+};
+
+const ACCESS_LOG_PROCESS: &str = "HttpAccessLog";
+
+// One log event per request. Registered after RequestIdMiddleware, so events have request id
+pub struct AccessLogMiddleware {
+    config: AccessLogConfig,
+    paths: Arc<HttpMetricsPaths>,
+    success_sampler: AccessLogSampler,
+    client_error_sampler: AccessLogSampler,
+}
+
+impl AccessLogMiddleware {
+    pub fn new(config: AccessLogConfig, paths: Arc<HttpMetricsPaths>) -> Self {
+        Self {
+            config,
+            paths,
+            success_sampler: AccessLogSampler::new(),
+            client_error_sampler: AccessLogSampler::new(),
+        }
+    }
+
+    fn is_sampled(&self, status_code: u16) -> bool {
+        if status_code >= 500 {
+            return true;
+        }
+
+        if status_code >= 400 {
+            return self
+                .client_error_sampler
+                .is_sampled(self.config.client_error_sample_rate);
+        }
+
+        self.success_sampler
+            .is_sampled(self.config.success_sample_rate)
+    }
+
+    fn get_headers(&self, ctx: &HttpContext) -> String {
+        let mut result = Vec::new();
+
+        for (name, value) in ctx.request.get_headers().iter() {
+            let value = if self.config.is_header_redacted(name.as_str()) {
+                crate::REDACTED_VALUE
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+
+            result.push(format!("{}: {}", name.as_str(), value));
+        }
+
+        result.join("; ")
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for AccessLogMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        let mut sw = Stopwatch::start_new();
+        let result = get_next.next(ctx).await;
+        sw.stop();
+
+        let status_code = crate::sdk_metrics::get_status_code(&result);
+
+        if !self.is_sampled(status_code) {
+            return result;
+        }
+
+        let level = if status_code >= 500 {
+            LogLevel::Warning
+        } else {
+            LogLevel::Info
+        };
+
+        // Checked before writing, since Seq reads my_logger::LOGGER without the filter
+        if !LOG_LEVEL_FILTER.is_enabled(level, ACCESS_LOG_PROCESS) {
+            return result;
+        }
+
+        let method = ctx.request.method.as_str().to_string();
+        let path = ctx.request.http_path.as_str().to_string();
+        let route = match self.paths.find_route(&method, &path) {
+            Some(route) => route.to_string(),
+            None if status_code == 404 => UNMATCHED_PATH_LABEL.to_string(),
+            None => path.clone(),
+        };
+
+        let mut log_ctx = LogEventCtx::new()
+            .add("Method", method.as_str())
+            .add("Route", route.as_str())
+            .add("Path", path.as_str())
+            .add("Status", status_code.to_string())
+            .add("DurationMs", sw.elapsed().as_millis().to_string())
+            .add(
+                "RequestBytes",
+                crate::sdk_metrics::get_request_size(ctx).to_string(),
+            )
+            .add("ClientIp", ctx.request.get_ip().get_real_ip().to_string());
+
+        // Added here as well, Seq does not get it from the sink
+        if let Some(request_id) = crate::get_request_id() {
+            log_ctx = log_ctx.add(REQUEST_ID_LOG_KEY, request_id);
+        }
+
+        if let Some(response_size) = crate::sdk_metrics::get_response_size(&result) {
+            log_ctx = log_ctx.add("ResponseBytes", response_size.to_string());
+        }
+
+        if let Some(query) = ctx.request.get_uri().query() {
+            log_ctx = log_ctx.add("Query", self.config.redact_query(query));
+        }
+
+        if let Some(credentials) = ctx.credentials.as_ref() {
+            log_ctx = log_ctx.add("UserId", credentials.get_id());
+        }
+
+        if self.config.log_headers {
+            log_ctx = log_ctx.add("Headers", self.get_headers(ctx));
+        }
+
+        let message = format!("{} {} {}", method, route, status_code);
+
+        if level == LogLevel::Warning {
+            my_logger::LOGGER.write_warning(ACCESS_LOG_PROCESS.to_string(), message, log_ctx);
+        } else {
+            my_logger::LOGGER.write_info(ACCESS_LOG_PROCESS.to_string(), message, log_ctx);
+        }
+
+        result
+    }
+}
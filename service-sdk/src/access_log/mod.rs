@@ -0,0 +1,5 @@
+mod access_log_config;
+mod access_log_middleware;
+
+pub use access_log_config::*;
+pub use access_log_middleware::*;
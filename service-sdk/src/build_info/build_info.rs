@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+pub const UNKNOWN_BUILD_INFO_VALUE: &str = "unknown";
+
+// Filled by service_sdk::macros::build_info!() at compile time of the service
+#[derive(Debug, Clone)]
+pub struct BuildInfo {
+    pub git_sha: &'static str,
+    pub build_timestamp: &'static str,
+    pub rustc: &'static str,
+}
+
+impl BuildInfo {
+    pub fn unknown() -> Self {
+        Self {
+            git_sha: UNKNOWN_BUILD_INFO_VALUE,
+            build_timestamp: UNKNOWN_BUILD_INFO_VALUE,
+            rustc: UNKNOWN_BUILD_INFO_VALUE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceVersion {
+    pub name: String,
+    pub version: String,
+    pub git_sha: String,
+    pub build_timestamp: String,
+    pub rustc: String,
+    pub features: Vec<&'static str>,
+}
+
+impl ServiceVersion {
+    pub fn new(name: &str, version: &str, build_info: &BuildInfo) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            git_sha: build_info.git_sha.to_string(),
+            build_timestamp: build_info.build_timestamp.to_string(),
+            rustc: build_info.rustc.to_string(),
+            features: get_sdk_features(),
+        }
+    }
+
+    // Gauge is always 1, the information is in the labels
+    pub fn write_metric(&self) {
+        let labels = &[
+            ("name", self.name.clone()),
+            ("version", self.version.clone()),
+            ("git_sha", self.git_sha.clone()),
+            ("rustc", self.rustc.clone()),
+            ("features", self.features.join(",")),
+        ];
+
+        metrics::gauge!("service_build_info", labels).set(1.0);
+    }
+}
+
+// SDK cargo features the service is compiled with
+pub fn get_sdk_features() -> Vec<&'static str> {
+    let mut result = vec![];
+
+    if cfg!(feature = "my-service-bus") {
+        result.push("my-service-bus");
+    }
+
+    if cfg!(feature = "my-nosql-data-reader-sdk") {
+        result.push("my-nosql-data-reader-sdk");
+    }
+
+    if cfg!(feature = "my-nosql-data-writer-sdk") {
+        result.push("my-nosql-data-writer-sdk");
+    }
+
+    if cfg!(feature = "grpc") {
+        result.push("grpc");
+    }
+
+    if cfg!(feature = "grpc-reflection") {
+        result.push("grpc-reflection");
+    }
+
+    if cfg!(feature = "grpc-with-tls") {
+        result.push("grpc-with-tls");
+    }
+
+    if cfg!(feature = "http-tls") {
+        result.push("http-tls");
+    }
+
+    if cfg!(feature = "postgres") {
+        result.push("postgres");
+    }
+
+    if cfg!(feature = "signal-r") {
+        result.push("signal-r");
+    }
+
+    if cfg!(feature = "with-ssh") {
+        result.push("with-ssh");
+    }
+
+    if cfg!(feature = "runtime-metrics") {
+        result.push("runtime-metrics");
+    }
+
+    if cfg!(feature = "opentelemetry") {
+        result.push("opentelemetry");
+    }
+
+    if cfg!(feature = "seq") {
+        result.push("seq");
+    }
+
+    result
+}
@@ -0,0 +1,5 @@
+mod build_info;
+mod version_middleware;
+
+pub use build_info::*;
+pub use version_middleware::*;
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+
+use crate::ServiceVersion;
+
+pub const VERSION_PATH: &str = "/api/version";
+
+pub struct VersionMiddleware {
+    service_version: Arc<ServiceVersion>,
+}
+
+impl VersionMiddleware {
+    pub fn new(service_version: Arc<ServiceVersion>) -> Self {
+        Self { service_version }
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for VersionMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        if ctx.request.http_path.as_str() != VERSION_PATH {
+            return get_next.next(ctx).await;
+        }
+
+        HttpOutput::as_json(self.service_version.as_ref().clone()).into_ok_result(false)
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+const MIN_ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(5);
+const MAX_ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+// accept() fails immediately while the process is out of file descriptors (EMFILE),
+// so retrying right away spins on the CPU. Delay doubles while errors repeat
+pub struct AcceptErrorBackoff {
+    delay: Duration,
+}
+
+impl AcceptErrorBackoff {
+    pub fn new() -> Self {
+        Self {
+            delay: MIN_ACCEPT_ERROR_DELAY,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.delay = MIN_ACCEPT_ERROR_DELAY;
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let result = self.delay;
+        self.delay = (self.delay * 2).min(MAX_ACCEPT_ERROR_DELAY);
+        result
+    }
+
+    pub async fn wait(&mut self) {
+        tokio::time::sleep(self.next_delay()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::AcceptErrorBackoff;
+
+    #[test]
+    fn test_delay_doubles_up_to_max() {
+        let mut backoff = AcceptErrorBackoff::new();
+
+        assert_eq!(backoff.next_delay(), Duration::from_millis(5));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(10));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(20));
+
+        for _ in 0..20 {
+            backoff.next_delay();
+        }
+
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut backoff = AcceptErrorBackoff::new();
+        backoff.next_delay();
+        backoff.next_delay();
+
+        backoff.reset();
+
+        assert_eq!(backoff.next_delay(), Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use is_alive_middleware::IsAliveMiddleware;
+use my_http_server::{HttpServerMiddleware, MyHttpServer};
+use rust_extensions::{AppStates, StrOrString};
+
+use crate::{
+    HealthCheckMiddleware, HealthCheckRegistry, LogLevelMiddleware, MetricsEndpointMiddleware,
+    ProbesMiddleware, ReadinessProbe,
+};
+
+// Separate listener for /metrics, probes and other diagnostics, so they are not exposed
+// together with the API and do not go through its middlewares
+pub struct AdminServerBuilder {
+    listen_address: SocketAddr,
+    app_name: String,
+    app_version: String,
+    readiness_probe: Arc<ReadinessProbe>,
+    health_check_registry: Arc<HealthCheckRegistry>,
+    custom_middlewares: Vec<Arc<dyn HttpServerMiddleware + Send + Sync + 'static>>,
+}
+
+impl AdminServerBuilder {
+    pub fn new(
+        port: u16,
+        app_name: StrOrString<'static>,
+        app_version: StrOrString<'static>,
+        readiness_probe: Arc<ReadinessProbe>,
+        health_check_registry: Arc<HealthCheckRegistry>,
+    ) -> Self {
+        Self {
+            listen_address: SocketAddr::new(crate::consts::get_default_ip_address(), port),
+            app_name: app_name.to_string(),
+            app_version: app_version.to_string(),
+            readiness_probe,
+            health_check_registry,
+            custom_middlewares: vec![],
+        }
+    }
+
+    pub fn get_listen_address(&self) -> SocketAddr {
+        self.listen_address
+    }
+
+    pub fn register_custom_middleware(
+        &mut self,
+        middleware: Arc<dyn HttpServerMiddleware + Send + Sync + 'static>,
+    ) {
+        self.custom_middlewares.push(middleware);
+    }
+
+    pub fn start(&mut self, app_states: Arc<AppStates>) -> MyHttpServer {
+        let mut admin_server = MyHttpServer::new(self.listen_address);
+
+        admin_server.add_middleware(Arc::new(IsAliveMiddleware::new(
+            self.app_name.clone(),
+            self.app_version.clone(),
+        )));
+        admin_server.add_middleware(Arc::new(ProbesMiddleware::new(
+            self.readiness_probe.clone(),
+        )));
+        admin_server.add_middleware(Arc::new(HealthCheckMiddleware::new(
+            self.health_check_registry.clone(),
+        )));
+        admin_server.add_middleware(Arc::new(MetricsEndpointMiddleware::new()));
+        admin_server.add_middleware(Arc::new(LogLevelMiddleware::new()));
+
+        for middleware in self.custom_middlewares.drain(..) {
+            admin_server.add_middleware(middleware);
+        }
+
+        admin_server.start(app_states, my_logger::LOGGER.clone());
+
+        admin_server
+    }
+}
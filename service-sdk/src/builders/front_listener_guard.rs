@@ -0,0 +1,207 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+
+pub const FRONT_LISTENER_TOKEN_HEADER: &str = "x-sdk-front-token";
+pub const CLIENT_CERT_HEADER: &str = "x-client-cert";
+pub const FRONT_LISTENER_CHECK_PATH: &str = "/__sdk/front-listener";
+
+tokio::task_local! {
+    static CLIENT_CERTIFICATE: Option<String>;
+}
+
+// PEM of the certificate presented by the client over mutual TLS to the current request
+pub fn get_client_certificate() -> Option<String> {
+    CLIENT_CERTIFICATE
+        .try_with(|client_certificate| client_certificate.clone())
+        .ok()
+        .flatten()
+}
+
+// Shared by HttpFrontListener and the loopback MyHttpServer it proxies to
+#[derive(Debug, Clone)]
+pub struct FrontListenerToken {
+    // Sent with every proxied request, so nothing else on the host can call loopback server
+    // with forged X-Forwarded-For or client certificate
+    pub request_token: String,
+    // Answer to FRONT_LISTENER_CHECK_PATH, so front listener knows the loopback port is taken
+    // by our server and not by another process
+    pub check_response: String,
+}
+
+impl FrontListenerToken {
+    pub fn new() -> Self {
+        Self {
+            request_token: uuid::Uuid::new_v4().simple().to_string(),
+            check_response: uuid::Uuid::new_v4().simple().to_string(),
+        }
+    }
+}
+
+// First middleware of MyHttpServer which listens behind HttpFrontListener
+pub struct FrontListenerGuardMiddleware {
+    token: FrontListenerToken,
+}
+
+impl FrontListenerGuardMiddleware {
+    pub fn new(token: FrontListenerToken) -> Self {
+        Self { token }
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for FrontListenerGuardMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        let headers = ctx.request.get_headers();
+
+        let is_from_front_listener = headers
+            .get(FRONT_LISTENER_TOKEN_HEADER)
+            .map(|value| value.as_bytes() == self.token.request_token.as_bytes())
+            .unwrap_or(false);
+
+        if !is_from_front_listener {
+            return HttpOutput::as_text("Forbidden".to_string()).into_fail_result(403, false);
+        }
+
+        if ctx.request.http_path.as_str() == FRONT_LISTENER_CHECK_PATH {
+            return HttpOutput::as_text(self.token.check_response.clone()).into_ok_result(false);
+        }
+
+        let client_certificate = headers
+            .get(CLIENT_CERT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                percent_encoding::percent_decode_str(value)
+                    .decode_utf8_lossy()
+                    .to_string()
+            });
+
+        with_client_certificate(client_certificate, get_next.next(ctx)).await
+    }
+}
+
+async fn with_client_certificate<TFuture: Future>(
+    client_certificate: Option<String>,
+    future: TFuture,
+) -> TFuture::Output {
+    CLIENT_CERTIFICATE.scope(client_certificate, future).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use my_http_server::{
+        HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpRequest, HttpServerMiddleware,
+        HttpServerRequestFlow,
+    };
+
+    use super::{
+        FrontListenerGuardMiddleware, FrontListenerToken, CLIENT_CERT_HEADER,
+        FRONT_LISTENER_CHECK_PATH, FRONT_LISTENER_TOKEN_HEADER,
+    };
+
+    // Returns the client certificate seen by the handler
+    struct ClientCertificateHandler;
+
+    #[async_trait]
+    impl HttpServerMiddleware for ClientCertificateHandler {
+        async fn handle_request(
+            &self,
+            _ctx: &mut HttpContext,
+            _get_next: &mut HttpServerRequestFlow,
+        ) -> Result<HttpOkResult, HttpFailResult> {
+            let client_certificate = super::get_client_certificate().unwrap_or_default();
+            HttpOutput::as_text(client_certificate).into_ok_result(false)
+        }
+    }
+
+    async fn handle(
+        token: &FrontListenerToken,
+        path: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        let mut header_map = hyper::HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(
+                hyper::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+
+        let mut ctx = HttpContext::new(HttpRequest::new("GET", path, header_map));
+        let mut flow = HttpServerRequestFlow::new(vec![
+            Arc::new(FrontListenerGuardMiddleware::new(token.clone())),
+            Arc::new(ClientCertificateHandler),
+        ]);
+
+        flow.next(&mut ctx).await
+    }
+
+    fn get_text(result: Result<HttpOkResult, HttpFailResult>) -> String {
+        match result.unwrap().output {
+            HttpOutput::Content { content, .. } => String::from_utf8(content).unwrap(),
+            _ => panic!("Content is expected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_without_token_is_rejected() {
+        let token = FrontListenerToken::new();
+
+        let result = handle(&token, "/api/test", &[]).await;
+        assert_eq!(result.unwrap_err().status_code, 403);
+
+        let result = handle(
+            &token,
+            "/api/test",
+            &[(FRONT_LISTENER_TOKEN_HEADER, "forged")],
+        )
+        .await;
+        assert_eq!(result.unwrap_err().status_code, 403);
+    }
+
+    #[tokio::test]
+    async fn test_check_path_returns_check_response() {
+        let token = FrontListenerToken::new();
+
+        let result = handle(
+            &token,
+            FRONT_LISTENER_CHECK_PATH,
+            &[(FRONT_LISTENER_TOKEN_HEADER, token.request_token.as_str())],
+        )
+        .await;
+
+        assert_eq!(get_text(result), token.check_response);
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate_is_decoded() {
+        let token = FrontListenerToken::new();
+
+        let result = handle(
+            &token,
+            "/api/test",
+            &[
+                (FRONT_LISTENER_TOKEN_HEADER, token.request_token.as_str()),
+                (
+                    CLIENT_CERT_HEADER,
+                    "-----BEGIN%20CERTIFICATE-----%0AMIIB%2B%3D%0A",
+                ),
+            ],
+        )
+        .await;
+
+        assert_eq!(get_text(result), "-----BEGIN CERTIFICATE-----\nMIIB+=\n");
+        assert!(super::get_client_certificate().is_none());
+    }
+}
@@ -1,31 +1,61 @@
 use std::{
     convert::Infallible,
+    future::Future,
     net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
 };
 
 use my_grpc_extensions::tonic::{
     body::BoxBody,
     codegen::{http::Request, Service},
     server::NamedService,
-    transport::{server::Router, Server},
+    transport::{
+        server::{Router, TcpIncoming},
+        Server,
+    },
 };
 
+#[cfg(feature = "grpc-with-tls")]
+use crate::{GrpcTlsIncoming, TlsCertificateSource, TlsConfig};
+
 use my_logger::LogEventCtx;
-use tokio::task::JoinHandle;
+use rust_extensions::AppStates;
+use tokio::{sync::Notify, task::JoinHandle};
 
-use crate::GrpcMetricsMiddlewareLayer;
+use crate::{
+    GrpcHealthStatusUpdater, GrpcMetricsMiddlewareLayer, GrpcRequestIdLayer, GrpcServerError,
+    HealthCheckRegistry, ReadinessProbe,
+};
 
 const DEFAULT_GRPC_PORT: u16 = 8888;
-pub struct GrpcServerBuilder {
-    server: Option<
-        Router<
-            tower::layer::util::Stack<
-                tower::layer::util::Stack<GrpcMetricsMiddlewareLayer, tower::layer::util::Identity>,
-                tower::layer::util::Identity,
-            >,
-        >,
+
+#[cfg(feature = "grpc-reflection")]
+const DISABLE_GRPC_REFLECTION_ENV: &str = "DISABLE_GRPC_REFLECTION";
+
+pub type GrpcServerLayer = tower::layer::util::Stack<
+    tower::layer::util::Stack<
+        GrpcMetricsMiddlewareLayer,
+        tower::layer::util::Stack<GrpcRequestIdLayer, tower::layer::util::Identity>,
     >,
+    tower::layer::util::Identity,
+>;
+
+pub type GrpcServerRouter = Router<GrpcServerLayer>;
+
+pub struct GrpcServerBuilder {
+    server: Option<GrpcServerRouter>,
     listen_address: Option<SocketAddr>,
+    service_names: Vec<&'static str>,
+    report_health_checks: bool,
+    #[cfg(feature = "grpc-reflection")]
+    reflection_file_descriptor_sets: Vec<&'static [u8]>,
+    #[cfg(feature = "grpc-reflection")]
+    reflection_disabled: bool,
+    #[cfg(feature = "grpc-with-tls")]
+    tls_certificate: Option<TlsCertificateSource>,
+    #[cfg(feature = "grpc-with-tls")]
+    tls_client_ca_path: Option<String>,
 }
 
 impl GrpcServerBuilder {
@@ -33,6 +63,16 @@ impl GrpcServerBuilder {
         Self {
             server: None,
             listen_address: None,
+            service_names: vec![],
+            report_health_checks: false,
+            #[cfg(feature = "grpc-reflection")]
+            reflection_file_descriptor_sets: vec![],
+            #[cfg(feature = "grpc-reflection")]
+            reflection_disabled: false,
+            #[cfg(feature = "grpc-with-tls")]
+            tls_certificate: None,
+            #[cfg(feature = "grpc-with-tls")]
+            tls_client_ca_path: None,
         }
     }
 
@@ -40,6 +80,96 @@ impl GrpcServerBuilder {
         self.listen_address = Some(SocketAddr::new(ip, port));
     }
 
+    // grpc.health.v1 reports NOT_SERVING when any of SDK health checks fails
+    pub fn report_health_checks(&mut self) {
+        self.report_health_checks = true;
+    }
+
+    // Certificate files are watched and reloaded when they are changed
+    #[cfg(feature = "grpc-with-tls")]
+    pub fn with_tls(&mut self, cert_path: impl Into<String>, key_path: impl Into<String>) {
+        self.tls_certificate = Some(TlsCertificateSource::from_files(cert_path, key_path));
+    }
+
+    #[cfg(feature = "grpc-with-tls")]
+    pub fn with_tls_pem(&mut self, cert_pem: impl Into<String>, key_pem: impl Into<String>) {
+        self.tls_certificate = Some(TlsCertificateSource::from_pem(cert_pem, key_pem));
+    }
+
+    // Enables mutual TLS. Client certificate is available in handlers with request.peer_certs()
+    #[cfg(feature = "grpc-with-tls")]
+    pub fn with_client_ca(&mut self, client_ca_path: impl Into<String>) {
+        self.tls_client_ca_path = Some(client_ca_path.into());
+    }
+
+    #[cfg(feature = "grpc-with-tls")]
+    fn get_tls_config(&mut self) -> Result<Option<TlsConfig>, GrpcServerError> {
+        let Some(certificate) = self.tls_certificate.take() else {
+            if self.tls_client_ca_path.is_some() {
+                return Err(GrpcServerError::Tls(
+                    "Client CA is set, but server certificate is not".to_string(),
+                ));
+            }
+
+            return Ok(None);
+        };
+
+        let mut tls_config = TlsConfig::new(certificate);
+        tls_config.client_ca_path = self.tls_client_ca_path.take();
+
+        Ok(Some(tls_config))
+    }
+
+    // Registers grpc.reflection service for the given encoded FileDescriptorSets.
+    // Can be switched off without recompiling with DISABLE_GRPC_REFLECTION env variable
+    #[cfg(feature = "grpc-reflection")]
+    pub fn enable_reflection(&mut self, file_descriptor_sets: &[&'static [u8]]) {
+        self.reflection_file_descriptor_sets
+            .extend_from_slice(file_descriptor_sets);
+    }
+
+    // Takes precedence over enable_reflection. Useful to switch it off by a setting or per environment
+    #[cfg(feature = "grpc-reflection")]
+    pub fn disable_reflection(&mut self) {
+        self.reflection_disabled = true;
+    }
+
+    #[cfg(feature = "grpc-reflection")]
+    fn add_reflection_services(&self, server: GrpcServerRouter) -> GrpcServerRouter {
+        if self.reflection_disabled
+            || self.reflection_file_descriptor_sets.is_empty()
+            || std::env::var(DISABLE_GRPC_REFLECTION_ENV).is_ok()
+        {
+            return server;
+        }
+
+        let mut v1 = tonic_reflection::server::Builder::configure();
+        let mut v1alpha = tonic_reflection::server::Builder::configure();
+
+        for file_descriptor_set in self.reflection_file_descriptor_sets.iter() {
+            v1 = v1.register_encoded_file_descriptor_set(file_descriptor_set);
+            v1alpha = v1alpha.register_encoded_file_descriptor_set(file_descriptor_set);
+        }
+
+        let v1 = v1
+            .build_v1()
+            .expect("Can not build grpc reflection service");
+        let v1alpha = v1alpha
+            .build_v1alpha()
+            .expect("Can not build grpc reflection service");
+
+        server.add_service(v1).add_service(v1alpha)
+    }
+
+    fn create_server() -> Server<GrpcServerLayer> {
+        let layer = tower::ServiceBuilder::new()
+            .layer(GrpcRequestIdLayer::default())
+            .layer(GrpcMetricsMiddlewareLayer::default())
+            .into_inner();
+
+        Server::builder().layer(layer)
+    }
+
     pub fn add_grpc_service<S>(&mut self, svc: S)
     where
         S: Service<
@@ -52,50 +182,28 @@ impl GrpcServerBuilder {
             + 'static,
         S::Future: Send + 'static,
     {
-        if self.server.is_some() {
-            panic!("Only one service can be added to the server");
-        }
-
-        let layer = tower::ServiceBuilder::new()
-            .layer(GrpcMetricsMiddlewareLayer::default())
-            .into_inner();
+        self.service_names.push(<S as NamedService>::NAME);
 
-        let server = Server::builder().layer(layer).add_service(svc);
+        let server = match self.server.take() {
+            Some(server) => server.add_service(svc),
+            None => Self::create_server().add_service(svc),
+        };
 
         self.server = Some(server);
     }
 
-    pub fn add_grpc_services(
-        &mut self,
-        add_function: impl Fn(
-            &mut Server<
-                tower::layer::util::Stack<
-                    tower::layer::util::Stack<
-                        GrpcMetricsMiddlewareLayer,
-                        tower::layer::util::Identity,
-                    >,
-                    tower::layer::util::Identity,
-                >,
-            >,
-        ) -> Router<
-            tower::layer::util::Stack<
-                tower::layer::util::Stack<GrpcMetricsMiddlewareLayer, tower::layer::util::Identity>,
-                tower::layer::util::Identity,
-            >,
-        >,
-    ) {
-        let layer = tower::ServiceBuilder::new()
-            .layer(GrpcMetricsMiddlewareLayer::default())
-            .into_inner();
-
-        let mut server = Server::builder().layer(layer);
-
-        let router = add_function(&mut server);
-
-        self.server = Some(router);
+    // Same as add_grpc_service for each of the services, can be mixed with it in any order.
+    // builder.add_grpc_services(|services| { services.add_grpc_service(a); services.add_grpc_service(b); })
+    pub fn add_grpc_services(&mut self, add_function: impl FnOnce(&mut Self)) {
+        add_function(self);
     }
 
-    pub fn build(&mut self) -> GrpcServer {
+    pub fn build(
+        &mut self,
+        app_states: Arc<AppStates>,
+        readiness_probe: Arc<ReadinessProbe>,
+        health_check_registry: Arc<HealthCheckRegistry>,
+    ) -> Result<GrpcServer, GrpcServerError> {
         let grpc_addr = if let Some(taken) = self.listen_address {
             taken
         } else {
@@ -110,51 +218,229 @@ impl GrpcServerBuilder {
             SocketAddr::new(crate::consts::get_default_ip_address(), grpc_port)
         };
 
-        let mut grpc_server = GrpcServer::new(self.server.take().unwrap());
-        grpc_server.start(grpc_addr);
+        let Some(server) = self.server.take() else {
+            return Err(GrpcServerError::NoServicesRegistered);
+        };
+
+        let (health_reporter, health_service) = tonic_health::server::health_reporter();
+        let server = server.add_service(health_service);
+
+        #[cfg(feature = "grpc-reflection")]
+        let server = self.add_reflection_services(server);
+
+        let mut grpc_server = GrpcServer::new(server);
 
-        grpc_server
+        #[cfg(feature = "grpc-with-tls")]
+        if let Some(tls_config) = self.get_tls_config()? {
+            grpc_server.set_tls_config(tls_config);
+        }
+
+        grpc_server.start(grpc_addr, app_states.clone())?;
+
+        GrpcHealthStatusUpdater::new(
+            health_reporter,
+            self.service_names.clone(),
+            app_states,
+            readiness_probe,
+            if self.report_health_checks {
+                Some(health_check_registry)
+            } else {
+                None
+            },
+        )
+        .start();
+
+        Ok(grpc_server)
     }
 }
 
 pub struct GrpcServer {
-    server: Option<
-        Router<
-            tower::layer::util::Stack<
-                tower::layer::util::Stack<GrpcMetricsMiddlewareLayer, tower::layer::util::Identity>,
-                tower::layer::util::Identity,
-            >,
-        >,
-    >,
+    server: Option<GrpcServerRouter>,
     join_handle: Option<JoinHandle<()>>,
+    stop_signal: Arc<Notify>,
+    #[cfg(feature = "grpc-with-tls")]
+    tls_config: Option<TlsConfig>,
 }
 
 impl GrpcServer {
-    pub fn new(
-        server: Router<
-            tower::layer::util::Stack<
-                tower::layer::util::Stack<GrpcMetricsMiddlewareLayer, tower::layer::util::Identity>,
-                tower::layer::util::Identity,
-            >,
-        >,
-    ) -> Self {
+    pub fn new(server: GrpcServerRouter) -> Self {
         Self {
             server: Some(server),
             join_handle: None,
+            stop_signal: Arc::new(Notify::new()),
+            #[cfg(feature = "grpc-with-tls")]
+            tls_config: None,
+        }
+    }
+
+    #[cfg(feature = "grpc-with-tls")]
+    pub fn set_tls_config(&mut self, tls_config: TlsConfig) {
+        self.tls_config = Some(tls_config);
+    }
+
+    // Binds the listener synchronously so a busy port is reported to the caller
+    // instead of panicking inside a detached task
+    pub fn start(
+        &mut self,
+        grpc_addr: SocketAddr,
+        app_states: Arc<AppStates>,
+    ) -> Result<(), GrpcServerError> {
+        let Some(server) = self.server.take() else {
+            return Err(GrpcServerError::AlreadyStarted);
+        };
+
+        let stop_signal = self.stop_signal.clone();
+        let shutdown = async move {
+            tokio::select! {
+                _ = app_states.wait_until_shutdown() => {}
+                _ = stop_signal.notified() => {}
+            }
+        };
+
+        #[cfg(feature = "grpc-with-tls")]
+        if let Some(tls_config) = self.tls_config.take() {
+            let server_config = tls_config
+                .build_server_config(&[b"h2"])
+                .map_err(GrpcServerError::Tls)?;
+
+            let listener = bind_tcp_listener(grpc_addr)?;
+            let incoming = GrpcTlsIncoming::new(listener, server_config);
+
+            self.spawn(grpc_addr, "GRPC server (TLS)", async move {
+                server
+                    .serve_with_incoming_shutdown(incoming, shutdown)
+                    .await
+            });
+
+            return Ok(());
         }
+
+        let incoming =
+            TcpIncoming::new(grpc_addr, true, None).map_err(|err| GrpcServerError::Bind {
+                address: grpc_addr,
+                error: err.to_string(),
+            })?;
+
+        self.spawn(grpc_addr, "GRPC server", async move {
+            server
+                .serve_with_incoming_shutdown(incoming, shutdown)
+                .await
+        });
+
+        Ok(())
     }
 
-    pub fn start(&mut self, grpc_addr: SocketAddr) {
+    fn spawn(
+        &mut self,
+        grpc_addr: SocketAddr,
+        server_name: &'static str,
+        serve: impl Future<Output = Result<(), my_grpc_extensions::tonic::transport::Error>>
+            + Send
+            + 'static,
+    ) {
         my_logger::LOGGER.write_info(
             "Starting GRPC Server".to_string(),
-            format!("GRPC server starts at: {:?}", &grpc_addr),
+            format!("{} starts at: {:?}", server_name, &grpc_addr),
             LogEventCtx::new(),
         );
 
-        let server = self.server.take().unwrap();
         let result = tokio::spawn(async move {
-            server.serve(grpc_addr).await.unwrap();
+            if let Err(err) = serve.await {
+                my_logger::LOGGER.write_fatal_error(
+                    "GRPC Server".to_string(),
+                    format!(
+                        "{} at {:?} stopped with error: {}",
+                        server_name, grpc_addr, err
+                    ),
+                    LogEventCtx::new(),
+                );
+            }
         });
         self.join_handle = Some(result);
     }
+
+    // Stops accepting new connections and waits until in-flight calls are finished
+    pub async fn stop(&mut self) {
+        self.stop_signal.notify_one();
+
+        if let Some(join_handle) = self.join_handle.take() {
+            let _ = join_handle.await;
+        }
+    }
+
+    // Server stops accepting connections once AppStates is shutting down and drains in-flight calls.
+    // Returns false if the drain did not finish within the timeout
+    pub async fn wait_until_stopped(&mut self, timeout: Duration) -> bool {
+        let Some(join_handle) = self.join_handle.take() else {
+            return true;
+        };
+
+        tokio::time::timeout(timeout, join_handle).await.is_ok()
+    }
+}
+
+#[cfg(feature = "grpc-with-tls")]
+fn bind_tcp_listener(grpc_addr: SocketAddr) -> Result<tokio::net::TcpListener, GrpcServerError> {
+    let to_error = |err: std::io::Error| GrpcServerError::Bind {
+        address: grpc_addr,
+        error: err.to_string(),
+    };
+
+    let listener = std::net::TcpListener::bind(grpc_addr).map_err(to_error)?;
+    listener.set_nonblocking(true).map_err(to_error)?;
+    tokio::net::TcpListener::from_std(listener).map_err(to_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use rust_extensions::AppStates;
+
+    use super::{GrpcServer, GrpcServerBuilder};
+    use crate::GrpcServerError;
+
+    fn get_free_addr() -> SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    fn create_grpc_server() -> GrpcServer {
+        let (_, health_service) = tonic_health::server::health_reporter();
+        GrpcServer::new(GrpcServerBuilder::create_server().add_service(health_service))
+    }
+
+    #[tokio::test]
+    async fn test_busy_port_is_reported_and_released_after_stop() {
+        let app_states = Arc::new(AppStates::create_initialized());
+        let grpc_addr = get_free_addr();
+
+        let mut grpc_server = create_grpc_server();
+        grpc_server.start(grpc_addr, app_states.clone()).unwrap();
+
+        let result = create_grpc_server().start(grpc_addr, app_states.clone());
+        assert!(matches!(result, Err(GrpcServerError::Bind { .. })));
+
+        let result = grpc_server.start(grpc_addr, app_states);
+        assert!(matches!(result, Err(GrpcServerError::AlreadyStarted)));
+
+        grpc_server.stop().await;
+
+        std::net::TcpListener::bind(grpc_addr).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_is_stopped_on_shutdown() {
+        let app_states = Arc::new(AppStates::create_initialized());
+        let grpc_addr = get_free_addr();
+
+        let mut grpc_server = create_grpc_server();
+        grpc_server.start(grpc_addr, app_states.clone()).unwrap();
+
+        app_states.set_shutting_down();
+
+        assert!(grpc_server.wait_until_stopped(Duration::from_secs(5)).await);
+
+        std::net::TcpListener::bind(grpc_addr).unwrap();
+    }
 }
@@ -0,0 +1,26 @@
+use std::net::SocketAddr;
+
+#[derive(Debug)]
+pub enum GrpcServerError {
+    NoServicesRegistered,
+    AlreadyStarted,
+    Bind { address: SocketAddr, error: String },
+    Tls(String),
+}
+
+impl std::fmt::Display for GrpcServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrpcServerError::NoServicesRegistered => {
+                write!(f, "At least one grpc service has to be added to the server")
+            }
+            GrpcServerError::AlreadyStarted => write!(f, "GRPC server is already started"),
+            GrpcServerError::Bind { address, error } => {
+                write!(f, "Can not bind GRPC server to {}: {}", address, error)
+            }
+            GrpcServerError::Tls(error) => write!(f, "Invalid GRPC server TLS setup: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for GrpcServerError {}
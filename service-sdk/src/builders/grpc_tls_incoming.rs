@@ -0,0 +1,98 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_core::Stream;
+use my_logger::LogEventCtx;
+use tokio::{net::TcpListener, sync::mpsc};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+use crate::AcceptErrorBackoff;
+
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Stream of already handshaked connections. tonic implements Connected for TlsStream,
+// so handlers can read client certificate with request.peer_certs()
+pub struct GrpcTlsIncoming {
+    receiver: mpsc::Receiver<Result<TlsStream<tokio::net::TcpStream>, std::io::Error>>,
+}
+
+impl GrpcTlsIncoming {
+    pub fn new(listener: TcpListener, server_config: rustls::ServerConfig) -> Self {
+        let acceptor = TlsAcceptor::from(Arc::new(server_config));
+        let (sender, receiver) = mpsc::channel(128);
+
+        tokio::spawn(async move {
+            let mut backoff = AcceptErrorBackoff::new();
+
+            loop {
+                let accepted = tokio::select! {
+                    _ = sender.closed() => return,
+                    accepted = listener.accept() => accepted,
+                };
+
+                let (tcp_stream, remote_addr) = match accepted {
+                    Ok(accepted) => {
+                        backoff.reset();
+                        accepted
+                    }
+                    Err(err) => {
+                        // tonic only traces accept errors, so they are logged here
+                        my_logger::LOGGER.write_warning(
+                            "GRPC TLS".to_string(),
+                            format!("Can not accept connection: {}", err),
+                            LogEventCtx::new(),
+                        );
+                        backoff.wait().await;
+                        continue;
+                    }
+                };
+
+                let _ = tcp_stream.set_nodelay(true);
+
+                let acceptor = acceptor.clone();
+                let sender = sender.clone();
+
+                // Handshake is done aside, so one slow client does not block accepting others
+                tokio::spawn(async move {
+                    let handshake =
+                        tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(tcp_stream))
+                            .await;
+
+                    match handshake {
+                        Ok(Ok(tls_stream)) => {
+                            let _ = sender.send(Ok(tls_stream)).await;
+                        }
+                        Ok(Err(err)) => {
+                            my_logger::LOGGER.write_warning(
+                                "GRPC TLS".to_string(),
+                                format!("TLS handshake with {} failed: {}", remote_addr, err),
+                                LogEventCtx::new(),
+                            );
+                        }
+                        Err(_) => {
+                            my_logger::LOGGER.write_warning(
+                                "GRPC TLS".to_string(),
+                                format!("TLS handshake with {} timed out", remote_addr),
+                                LogEventCtx::new(),
+                            );
+                        }
+                    }
+                });
+            }
+        });
+
+        Self { receiver }
+    }
+}
+
+impl Stream for GrpcTlsIncoming {
+    type Item = Result<TlsStream<tokio::net::TcpStream>, std::io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
@@ -0,0 +1,793 @@
+use std::{
+    convert::Infallible,
+    io::IoSlice,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use http_body_util::{combinators::BoxBody, BodyExt, Empty, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{HeaderValue, HOST},
+    Request, Response, StatusCode, Uri, Version,
+};
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+};
+use my_logger::LogEventCtx;
+use rust_extensions::AppStates;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{
+    AcceptErrorBackoff, FrontListenerToken, HttpProtocol, TlsConfig, CLIENT_CERT_HEADER,
+    FRONT_LISTENER_CHECK_PATH, FRONT_LISTENER_TOKEN_HEADER,
+};
+
+const HTTP2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+// MyHttpServer binds the loopback address in background after start
+const UPSTREAM_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+const UPSTREAM_CHECK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+
+type ProxyBody = BoxBody<Bytes, hyper::Error>;
+
+// Takes the public address when MyHttpServer can not serve it directly: terminates TLS
+// and/or serves both HTTP/1 and HTTP/2. Requests are proxied to MyHttpServer on loopback
+// with X-Forwarded-For, X-Forwarded-Proto and client certificate headers
+pub struct HttpFrontListener {
+    listen_address: SocketAddr,
+    upstream: SocketAddr,
+    upstream_http2: bool,
+    protocol: HttpProtocol,
+    token: FrontListenerToken,
+    trust_forwarded_headers: bool,
+    tls_config: Option<TlsConfig>,
+}
+
+impl HttpFrontListener {
+    pub fn new(
+        listen_address: SocketAddr,
+        upstream: SocketAddr,
+        protocol: HttpProtocol,
+        token: FrontListenerToken,
+    ) -> Self {
+        Self {
+            listen_address,
+            upstream,
+            upstream_http2: protocol == HttpProtocol::Http2,
+            protocol,
+            token,
+            trust_forwarded_headers: false,
+            tls_config: None,
+        }
+    }
+
+    pub fn set_tls_config(&mut self, tls_config: TlsConfig) {
+        self.tls_config = Some(tls_config);
+    }
+
+    // Keep X-Forwarded-For and X-Forwarded-Proto set by a load balancer in front of the service.
+    // Otherwise they are replaced, since clients can send anything there
+    pub fn set_trust_forwarded_headers(&mut self, value: bool) {
+        self.trust_forwarded_headers = value;
+    }
+
+    pub async fn start(self, app_states: Arc<AppStates>) -> Result<(), String> {
+        let acceptor = match self.tls_config.as_ref() {
+            Some(tls_config) => Some(tokio_rustls::TlsAcceptor::from(Arc::new(
+                tls_config.build_server_config(self.protocol.get_alpn_protocols())?,
+            ))),
+            None => None,
+        };
+
+        let client: Client<HttpConnector, ProxyBody> = Client::builder(TokioExecutor::new())
+            .http2_only(self.upstream_http2)
+            .build_http();
+
+        check_upstream(&client, self.upstream, &self.token).await?;
+
+        let listener = std::net::TcpListener::bind(self.listen_address)
+            .map_err(|err| format!("Can not bind {}: {}", self.listen_address, err))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|err| err.to_string())?;
+        let listener = TcpListener::from_std(listener).map_err(|err| err.to_string())?;
+
+        my_logger::LOGGER.write_info(
+            "Starting HTTP front listener".to_string(),
+            format!(
+                "HTTP front listener starts at: {:?}, protocol: {:?}, upstream: {:?}",
+                self.listen_address, self.protocol, self.upstream
+            ),
+            LogEventCtx::new(),
+        );
+
+        let proxy = Arc::new(UpstreamProxy {
+            client,
+            upstream: self.upstream,
+            upstream_http2: self.upstream_http2,
+            token: self.token,
+            trust_forwarded_headers: self.trust_forwarded_headers,
+        });
+        let protocol = self.protocol;
+
+        tokio::spawn(async move {
+            let mut backoff = AcceptErrorBackoff::new();
+
+            loop {
+                let accepted = tokio::select! {
+                    _ = app_states.wait_until_shutdown() => return,
+                    accepted = listener.accept() => accepted,
+                };
+
+                let (tcp_stream, remote_addr) = match accepted {
+                    Ok(accepted) => {
+                        backoff.reset();
+                        accepted
+                    }
+                    Err(err) => {
+                        my_logger::LOGGER.write_warning(
+                            "HTTP front listener".to_string(),
+                            format!("Can not accept connection: {}", err),
+                            LogEventCtx::new(),
+                        );
+                        backoff.wait().await;
+                        continue;
+                    }
+                };
+
+                let _ = tcp_stream.set_nodelay(true);
+
+                let connection = ConnectionInfo {
+                    proxy: proxy.clone(),
+                    remote_addr,
+                    is_tls: false,
+                    client_certificate: None,
+                };
+
+                let acceptor = acceptor.clone();
+
+                tokio::spawn(async move {
+                    let result = match acceptor {
+                        Some(acceptor) => {
+                            handle_tls_connection(acceptor, tcp_stream, protocol, connection).await
+                        }
+                        None => handle_connection(tcp_stream, protocol, None, connection).await,
+                    };
+
+                    if let Err(err) = result {
+                        my_logger::LOGGER.write_warning(
+                            "HTTP front listener".to_string(),
+                            format!("Connection from {} failed: {}", remote_addr, err),
+                            LogEventCtx::new(),
+                        );
+                    }
+                });
+            }
+        });
+
+        Ok(())
+    }
+}
+
+// The loopback port is reserved by binding and releasing it, so another process can take it
+// before MyHttpServer binds. Requests are not proxied until the server proves it is ours
+async fn check_upstream(
+    client: &Client<HttpConnector, ProxyBody>,
+    upstream: SocketAddr,
+    token: &FrontListenerToken,
+) -> Result<(), String> {
+    let uri: Uri = format!("http://{}{}", upstream, FRONT_LISTENER_CHECK_PATH)
+        .parse()
+        .map_err(|err: hyper::http::uri::InvalidUri| err.to_string())?;
+
+    let started = tokio::time::Instant::now();
+
+    loop {
+        let request = Request::get(uri.clone())
+            .header(FRONT_LISTENER_TOKEN_HEADER, token.request_token.as_str())
+            .body(Empty::new().map_err(|never| match never {}).boxed())
+            .map_err(|err| err.to_string())?;
+
+        match client.request(request).await {
+            Ok(response) => {
+                let status = response.status();
+                let body = response
+                    .into_body()
+                    .collect()
+                    .await
+                    .map(|body| body.to_bytes())
+                    .unwrap_or_default();
+
+                if status == StatusCode::OK && body == token.check_response.as_bytes() {
+                    return Ok(());
+                }
+
+                return Err(format!(
+                    "Loopback address {} is taken by another process",
+                    upstream
+                ));
+            }
+            Err(err) => {
+                if started.elapsed() >= UPSTREAM_CHECK_TIMEOUT {
+                    return Err(format!(
+                        "HTTP server at {} did not start: {}",
+                        upstream, err
+                    ));
+                }
+
+                tokio::time::sleep(UPSTREAM_CHECK_RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+struct UpstreamProxy {
+    client: Client<HttpConnector, ProxyBody>,
+    upstream: SocketAddr,
+    upstream_http2: bool,
+    token: FrontListenerToken,
+    trust_forwarded_headers: bool,
+}
+
+struct ConnectionInfo {
+    proxy: Arc<UpstreamProxy>,
+    remote_addr: SocketAddr,
+    is_tls: bool,
+    // URL encoded PEM, the same way as nginx $ssl_client_escaped_cert
+    client_certificate: Option<HeaderValue>,
+}
+
+async fn handle_tls_connection(
+    acceptor: tokio_rustls::TlsAcceptor,
+    tcp_stream: TcpStream,
+    protocol: HttpProtocol,
+    mut connection: ConnectionInfo,
+) -> Result<(), String> {
+    let tls_stream = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(tcp_stream))
+        .await
+        .map_err(|_| "TLS handshake timeout".to_string())?
+        .map_err(|err| format!("TLS handshake failed: {}", err))?;
+
+    let tls_connection = tls_stream.get_ref().1;
+
+    let negotiated_http2 = tls_connection.alpn_protocol().map(|alpn| alpn == b"h2");
+
+    connection.is_tls = true;
+    connection.client_certificate = tls_connection
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+        .and_then(|cert| HeaderValue::from_str(&encode_certificate(cert)).ok());
+
+    handle_connection(tls_stream, protocol, negotiated_http2, connection).await
+}
+
+async fn handle_connection<TStream>(
+    mut stream: TStream,
+    protocol: HttpProtocol,
+    negotiated_http2: Option<bool>,
+    connection: ConnectionInfo,
+) -> Result<(), String>
+where
+    TStream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut received = Vec::with_capacity(HTTP2_PREFACE.len());
+
+    let is_http2 = match (protocol, negotiated_http2) {
+        (HttpProtocol::Http1, _) => false,
+        (HttpProtocol::Http2, _) => true,
+        (HttpProtocol::Auto, Some(negotiated_http2)) => negotiated_http2,
+        (HttpProtocol::Auto, None) => {
+            tokio::time::timeout(HANDSHAKE_TIMEOUT, read_preface(&mut stream, &mut received))
+                .await
+                .map_err(|_| "Timeout while detecting protocol".to_string())??
+        }
+    };
+
+    let io = TokioIo::new(PrefixedStream::new(received, stream));
+    let connection = Arc::new(connection);
+
+    let service = hyper::service::service_fn(move |request| {
+        let connection = connection.clone();
+        async move { Ok::<_, Infallible>(proxy_request(request, &connection).await) }
+    });
+
+    if is_http2 {
+        hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+            .timer(TokioTimer::new())
+            .serve_connection(io, service)
+            .await
+            .map_err(|err| err.to_string())
+    } else {
+        hyper::server::conn::http1::Builder::new()
+            .timer(TokioTimer::new())
+            .serve_connection(io, service)
+            .with_upgrades()
+            .await
+            .map_err(|err| err.to_string())
+    }
+}
+
+async fn proxy_request(
+    mut request: Request<Incoming>,
+    connection: &ConnectionInfo,
+) -> Response<ProxyBody> {
+    let proxy = connection.proxy.as_ref();
+
+    if let Err(err) = prepare_upstream_request(&mut request, connection) {
+        return create_error_response(StatusCode::BAD_REQUEST, err);
+    }
+
+    // WebSocket (SignalR) and other HTTP/1 upgrades are piped after both sides switched
+    let client_upgrade = if request.headers().contains_key(hyper::header::UPGRADE) {
+        Some(hyper::upgrade::on(&mut request))
+    } else {
+        None
+    };
+
+    let request = request.map(|body| body.boxed());
+
+    let mut response = match proxy.client.request(request).await {
+        Ok(response) => response,
+        Err(err) => {
+            my_logger::LOGGER.write_warning(
+                "HTTP front listener".to_string(),
+                format!("Upstream {} request failed: {}", proxy.upstream, err),
+                LogEventCtx::new(),
+            );
+
+            return create_error_response(StatusCode::BAD_GATEWAY, "Bad gateway".to_string());
+        }
+    };
+
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+        if let Some(client_upgrade) = client_upgrade {
+            let upstream_upgrade = hyper::upgrade::on(&mut response);
+
+            tokio::spawn(async move {
+                let (Ok(client), Ok(upstream)) = tokio::join!(client_upgrade, upstream_upgrade)
+                else {
+                    return;
+                };
+
+                let _ = tokio::io::copy_bidirectional(
+                    &mut TokioIo::new(client),
+                    &mut TokioIo::new(upstream),
+                )
+                .await;
+            });
+        }
+    }
+
+    response.map(|body| body.boxed())
+}
+
+fn prepare_upstream_request(
+    request: &mut Request<Incoming>,
+    connection: &ConnectionInfo,
+) -> Result<(), String> {
+    let proxy = connection.proxy.as_ref();
+
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|itm| itm.as_str())
+        .unwrap_or("/");
+
+    let uri: Uri = format!("http://{}{}", proxy.upstream, path_and_query)
+        .parse()
+        .map_err(|err: hyper::http::uri::InvalidUri| err.to_string())?;
+
+    // HTTP/2 clients send authority instead of Host header
+    if !request.headers().contains_key(HOST) {
+        if let Some(authority) = request.uri().authority() {
+            if let Ok(host) = HeaderValue::from_str(authority.as_str()) {
+                request.headers_mut().insert(HOST, host);
+            }
+        }
+    }
+
+    *request.uri_mut() = uri;
+    *request.version_mut() = if proxy.upstream_http2 {
+        Version::HTTP_2
+    } else {
+        Version::HTTP_11
+    };
+
+    let headers = request.headers_mut();
+
+    let remote_ip = connection.remote_addr.ip().to_string();
+    let forwarded_for = match headers.get(X_FORWARDED_FOR) {
+        Some(existing) if proxy.trust_forwarded_headers => {
+            format!("{}, {}", existing.to_str().unwrap_or_default(), remote_ip)
+        }
+        _ => remote_ip,
+    };
+
+    if let Ok(forwarded_for) = HeaderValue::from_str(&forwarded_for) {
+        headers.insert(X_FORWARDED_FOR, forwarded_for);
+    }
+
+    if !(proxy.trust_forwarded_headers && headers.contains_key(X_FORWARDED_PROTO)) {
+        let proto = if connection.is_tls { "https" } else { "http" };
+        headers.insert(X_FORWARDED_PROTO, HeaderValue::from_static(proto));
+    }
+
+    headers.remove(CLIENT_CERT_HEADER);
+    if let Some(client_certificate) = connection.client_certificate.as_ref() {
+        headers.insert(CLIENT_CERT_HEADER, client_certificate.clone());
+    }
+
+    headers.insert(
+        FRONT_LISTENER_TOKEN_HEADER,
+        HeaderValue::from_str(&proxy.token.request_token).map_err(|err| err.to_string())?,
+    );
+
+    Ok(())
+}
+
+fn create_error_response(status: StatusCode, text: String) -> Response<ProxyBody> {
+    let mut response = Response::new(
+        Full::new(Bytes::from(text))
+            .map_err(|never| match never {})
+            .boxed(),
+    );
+    *response.status_mut() = status;
+    response
+}
+
+fn encode_certificate(cert: &rustls::pki_types::CertificateDer<'_>) -> String {
+    use base64::Engine;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(cert.as_ref());
+
+    let mut pem = "-----BEGIN CERTIFICATE-----\n".to_string();
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+
+    percent_encoding::utf8_percent_encode(&pem, percent_encoding::NON_ALPHANUMERIC).to_string()
+}
+
+// Reads until the received bytes either match HTTP/2 preface or diverge from it
+async fn read_preface(
+    stream: &mut (impl AsyncRead + Unpin),
+    received: &mut Vec<u8>,
+) -> Result<bool, String> {
+    let mut buffer = [0u8; 24];
+
+    while received.len() < HTTP2_PREFACE.len() {
+        let read = stream
+            .read(&mut buffer[..HTTP2_PREFACE.len() - received.len()])
+            .await
+            .map_err(|err| err.to_string())?;
+
+        if read == 0 {
+            return Err("Connection closed before request".to_string());
+        }
+
+        received.extend_from_slice(&buffer[..read]);
+
+        if !HTTP2_PREFACE.starts_with(received) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// Gives the bytes consumed while detecting the protocol back to the HTTP server
+struct PrefixedStream<TStream> {
+    prefix: Vec<u8>,
+    position: usize,
+    inner: TStream,
+}
+
+impl<TStream> PrefixedStream<TStream> {
+    fn new(prefix: Vec<u8>, inner: TStream) -> Self {
+        Self {
+            prefix,
+            position: 0,
+            inner,
+        }
+    }
+}
+
+impl<TStream: AsyncRead + Unpin> AsyncRead for PrefixedStream<TStream> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if self.position < self.prefix.len() {
+            let remaining = &self.prefix[self.position..];
+            let len = remaining.len().min(buf.remaining());
+            buf.put_slice(&remaining[..len]);
+            self.position += len;
+            return Poll::Ready(Ok(()));
+        }
+
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<TStream: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<TStream> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+// MyHttpServer binds the address itself, so we reserve a free loopback port for it.
+// HttpFrontListener checks that the server which took it is ours
+pub fn reserve_loopback_address() -> Result<SocketAddr, String> {
+    let listener = std::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+        .map_err(|err| format!("Can not reserve loopback port: {}", err))?;
+
+    listener.local_addr().map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+    use http_body_util::{BodyExt, Empty, Full};
+    use hyper::{
+        body::{Bytes, Incoming},
+        Request, Response,
+    };
+    use hyper_util::{
+        client::legacy::Client,
+        rt::{TokioExecutor, TokioIo},
+    };
+    use rust_extensions::AppStates;
+    use tokio::net::TcpListener;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::{
+        read_preface, HttpFrontListener, PrefixedStream, HTTP2_PREFACE, X_FORWARDED_FOR,
+        X_FORWARDED_PROTO,
+    };
+    use crate::{
+        FrontListenerToken, HttpProtocol, CLIENT_CERT_HEADER, FRONT_LISTENER_CHECK_PATH,
+        FRONT_LISTENER_TOKEN_HEADER,
+    };
+
+    // Stands for MyHttpServer with FrontListenerGuardMiddleware: answers the check
+    // and returns the headers it got
+    async fn start_upstream(check_response: Option<String>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let check_response = check_response.clone();
+
+                tokio::spawn(async move {
+                    let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+                        let check_response = check_response.clone();
+                        async move {
+                            let body = if request.uri().path() == FRONT_LISTENER_CHECK_PATH {
+                                check_response.unwrap_or_else(|| "Hello".to_string())
+                            } else {
+                                [
+                                    X_FORWARDED_FOR,
+                                    X_FORWARDED_PROTO,
+                                    CLIENT_CERT_HEADER,
+                                    FRONT_LISTENER_TOKEN_HEADER,
+                                ]
+                                .iter()
+                                .map(|name| {
+                                    let value = request
+                                        .headers()
+                                        .get(*name)
+                                        .map(|value| value.to_str().unwrap().to_string())
+                                        .unwrap_or_default();
+                                    format!("{}={}", name, value)
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                            };
+
+                            Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+                        }
+                    });
+
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        address
+    }
+
+    async fn start_front_listener(
+        token: FrontListenerToken,
+        upstream: SocketAddr,
+        trust_forwarded_headers: bool,
+    ) -> SocketAddr {
+        let listen_address = crate::reserve_loopback_address().unwrap();
+        let mut front_listener =
+            HttpFrontListener::new(listen_address, upstream, HttpProtocol::Auto, token);
+        front_listener.set_trust_forwarded_headers(trust_forwarded_headers);
+
+        front_listener
+            .start(Arc::new(AppStates::create_initialized()))
+            .await
+            .unwrap();
+
+        listen_address
+    }
+
+    async fn get(address: SocketAddr, headers: &[(&str, &str)]) -> String {
+        let client = Client::builder(TokioExecutor::new()).build_http::<Empty<Bytes>>();
+
+        let mut request = Request::get(format!("http://{}/api/test", address));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let response = client
+            .request(request.body(Empty::new()).unwrap())
+            .await
+            .unwrap();
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_headers_are_replaced() {
+        let token = FrontListenerToken::new();
+        let upstream = start_upstream(Some(token.check_response.clone())).await;
+        let address = start_front_listener(token.clone(), upstream, false).await;
+
+        let received = get(
+            address,
+            &[
+                (X_FORWARDED_FOR, "1.2.3.4"),
+                (X_FORWARDED_PROTO, "https"),
+                (CLIENT_CERT_HEADER, "forged"),
+                (FRONT_LISTENER_TOKEN_HEADER, "forged"),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            received,
+            format!(
+                "{}=127.0.0.1\n{}=http\n{}=\n{}={}",
+                X_FORWARDED_FOR,
+                X_FORWARDED_PROTO,
+                CLIENT_CERT_HEADER,
+                FRONT_LISTENER_TOKEN_HEADER,
+                token.request_token
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trusted_forwarded_headers_are_kept() {
+        let token = FrontListenerToken::new();
+        let upstream = start_upstream(Some(token.check_response.clone())).await;
+        let address = start_front_listener(token, upstream, true).await;
+
+        let received = get(
+            address,
+            &[(X_FORWARDED_FOR, "1.2.3.4"), (X_FORWARDED_PROTO, "https")],
+        )
+        .await;
+
+        assert!(received.starts_with(&format!(
+            "{}=1.2.3.4, 127.0.0.1\n{}=https\n",
+            X_FORWARDED_FOR, X_FORWARDED_PROTO
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_foreign_upstream_is_rejected() {
+        let token = FrontListenerToken::new();
+        let upstream = start_upstream(None).await;
+        let listen_address = crate::reserve_loopback_address().unwrap();
+
+        let result = HttpFrontListener::new(listen_address, upstream, HttpProtocol::Http1, token)
+            .start(Arc::new(AppStates::create_initialized()))
+            .await;
+
+        assert!(result.unwrap_err().contains("taken by another process"));
+    }
+
+    #[tokio::test]
+    async fn test_read_preface_detects_http2() {
+        let mut stream: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00";
+        let mut received = vec![];
+
+        assert!(read_preface(&mut stream, &mut received).await.unwrap());
+        assert_eq!(received, HTTP2_PREFACE);
+    }
+
+    #[tokio::test]
+    async fn test_read_preface_stops_on_http1() {
+        let mut stream: &[u8] = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let mut received = vec![];
+
+        assert!(!read_preface(&mut stream, &mut received).await.unwrap());
+        // Never reads more than the preface length, the rest stays in the stream
+        assert_eq!(received, b"GET / HTTP/1.1\r\nHost: lo");
+        assert_eq!(stream, b"calhost\r\n\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_read_preface_handles_split_preface() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+
+        tokio::spawn(async move {
+            client.write_all(&HTTP2_PREFACE[..5]).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            client.write_all(&HTTP2_PREFACE[5..]).await.unwrap();
+        });
+
+        let mut received = vec![];
+        assert!(read_preface(&mut server, &mut received).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_read_preface_fails_on_closed_connection() {
+        let mut stream: &[u8] = b"PRI * HTTP";
+        let mut received = vec![];
+
+        assert!(read_preface(&mut stream, &mut received).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prefixed_stream_replays_received_bytes() {
+        let (mut client, server) = tokio::io::duplex(64);
+        client.write_all(b"ET / HTTP/1.1").await.unwrap();
+        drop(client);
+
+        let mut stream = PrefixedStream::new(b"G".to_vec(), server);
+        let mut result = String::new();
+        stream.read_to_string(&mut result).await.unwrap();
+
+        assert_eq!(result, "GET / HTTP/1.1");
+    }
+}
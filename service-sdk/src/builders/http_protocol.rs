@@ -0,0 +1,140 @@
+use my_logger::LogEventCtx;
+
+pub const HTTP_PROTOCOL_ENV: &str = "HTTP_PROTOCOL";
+pub const LEGACY_HTTP2_ENV: &str = "HTTP2";
+
+#[cfg(feature = "http-tls")]
+const SUPPORTED_VALUES: &str = "http1, http2, auto";
+#[cfg(not(feature = "http-tls"))]
+const SUPPORTED_VALUES: &str = "http1, http2 (auto needs http-tls feature)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpProtocol {
+    Http1,
+    Http2,
+    // One listener serves both: ALPN decides with TLS, HTTP/2 preface (h2c prior knowledge) without it.
+    // Served by HttpFrontListener, so it needs http-tls feature
+    #[cfg(feature = "http-tls")]
+    Auto,
+}
+
+impl HttpProtocol {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "http1" | "http/1.1" | "h1" => Some(Self::Http1),
+            "http2" | "h2" | "h2c" => Some(Self::Http2),
+            #[cfg(feature = "http-tls")]
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    // Protocol from the environment wins over the one set in code.
+    // HTTP2 variable is kept for the services which already use it
+    pub fn resolve(
+        configured: HttpProtocol,
+        protocol_env: Option<&str>,
+        legacy_http2_env_is_set: bool,
+    ) -> HttpProtocol {
+        if let Some(protocol_env) = protocol_env {
+            if let Some(protocol) = Self::parse(protocol_env) {
+                return protocol;
+            }
+        }
+
+        if legacy_http2_env_is_set {
+            return HttpProtocol::Http2;
+        }
+
+        configured
+    }
+
+    pub fn resolve_from_env(configured: HttpProtocol) -> HttpProtocol {
+        let protocol_env = std::env::var(HTTP_PROTOCOL_ENV).ok();
+
+        if let Some(value) = protocol_env.as_deref() {
+            if Self::parse(value).is_none() {
+                my_logger::LOGGER.write_warning(
+                    "HTTP protocol".to_string(),
+                    format!(
+                        "Unknown {} value '{}'. Expected one of: {}. It is ignored",
+                        HTTP_PROTOCOL_ENV, value, SUPPORTED_VALUES
+                    ),
+                    LogEventCtx::new(),
+                );
+            }
+        }
+
+        Self::resolve(
+            configured,
+            protocol_env.as_deref(),
+            std::env::var(LEGACY_HTTP2_ENV).is_ok(),
+        )
+    }
+
+    #[cfg(feature = "http-tls")]
+    pub fn get_alpn_protocols(&self) -> &'static [&'static [u8]] {
+        match self {
+            HttpProtocol::Http1 => &[b"http/1.1"],
+            HttpProtocol::Http2 => &[b"h2"],
+            HttpProtocol::Auto => &[b"h2", b"http/1.1"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpProtocol;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(HttpProtocol::parse("http1"), Some(HttpProtocol::Http1));
+        assert_eq!(HttpProtocol::parse("HTTP/1.1"), Some(HttpProtocol::Http1));
+        assert_eq!(HttpProtocol::parse("h2c"), Some(HttpProtocol::Http2));
+        assert_eq!(HttpProtocol::parse(" Http2 "), Some(HttpProtocol::Http2));
+        assert_eq!(HttpProtocol::parse("http3"), None);
+        assert_eq!(HttpProtocol::parse(""), None);
+    }
+
+    #[test]
+    fn test_resolve_without_env_uses_configured() {
+        let result = HttpProtocol::resolve(HttpProtocol::Http2, None, false);
+        assert_eq!(result, HttpProtocol::Http2);
+    }
+
+    #[test]
+    fn test_resolve_protocol_env_wins() {
+        let result = HttpProtocol::resolve(HttpProtocol::Http2, Some("http1"), true);
+        assert_eq!(result, HttpProtocol::Http1);
+    }
+
+    #[cfg(feature = "http-tls")]
+    #[test]
+    fn test_auto() {
+        assert_eq!(HttpProtocol::parse("auto"), Some(HttpProtocol::Auto));
+
+        let result = HttpProtocol::resolve(HttpProtocol::Http1, Some("auto"), true);
+        assert_eq!(result, HttpProtocol::Auto);
+    }
+
+    #[cfg(not(feature = "http-tls"))]
+    #[test]
+    fn test_auto_needs_http_tls() {
+        assert_eq!(HttpProtocol::parse("auto"), None);
+    }
+
+    #[test]
+    fn test_resolve_legacy_http2_env() {
+        let result = HttpProtocol::resolve(HttpProtocol::Http1, None, true);
+        assert_eq!(result, HttpProtocol::Http2);
+    }
+
+    #[test]
+    fn test_resolve_ignores_unknown_protocol_env() {
+        let result = HttpProtocol::resolve(HttpProtocol::Http2, Some("http3"), false);
+        assert_eq!(result, HttpProtocol::Http2);
+
+        let result = HttpProtocol::resolve(HttpProtocol::Http1, Some("http3"), true);
+        assert_eq!(result, HttpProtocol::Http2);
+    }
+}
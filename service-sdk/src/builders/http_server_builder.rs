@@ -14,9 +14,19 @@ use my_http_server::controllers::{
     },
 };
 use my_http_server::{HttpServerMiddleware, MyHttpServer};
-use rust_extensions::StrOrString;
+use rust_extensions::{AppStates, StrOrString};
 
-use crate::MetricsMiddleware;
+use crate::{
+    AccessLogConfig, AccessLogMiddleware, DrainMiddleware, HealthCheckMiddleware, HttpMetricsPaths,
+    HttpProtocol, MetricsEndpointMiddleware, MetricsMiddleware, ProbesMiddleware,
+    RequestIdMiddleware, VersionMiddleware, DEFAULT_MAX_PATH_LABEL_VALUES,
+};
+
+#[cfg(feature = "http-tls")]
+use crate::{
+    FrontListenerGuardMiddleware, FrontListenerToken, HttpFrontListener, TlsCertificateSource,
+    TlsConfig,
+};
 
 pub struct HttpServerBuilder {
     listen_address: SocketAddr,
@@ -25,6 +35,23 @@ pub struct HttpServerBuilder {
     app_version: String,
     controllers: Option<ControllersMiddleware>,
     custom_middlewares: Vec<Arc<dyn HttpServerMiddleware + Send + Sync + 'static>>,
+    drain_middleware: Option<Arc<DrainMiddleware>>,
+    probes_middleware: Option<Arc<ProbesMiddleware>>,
+    health_check_middleware: Option<Arc<HealthCheckMiddleware>>,
+    version_middleware: Option<Arc<VersionMiddleware>>,
+    #[cfg(feature = "http-tls")]
+    tls_certificate: Option<TlsCertificateSource>,
+    #[cfg(feature = "http-tls")]
+    tls_client_ca_path: Option<String>,
+    protocol: HttpProtocol,
+    #[cfg(feature = "http-tls")]
+    trust_forwarded_headers: bool,
+    #[cfg(feature = "http-tls")]
+    front_listener: Option<HttpFrontListener>,
+    metrics_routes: Vec<(&'static str, String)>,
+    max_metrics_path_label_values: usize,
+    metrics_endpoint_enabled: bool,
+    access_log_config: Option<AccessLogConfig>,
 }
 impl HttpServerBuilder {
     pub fn new(app_name: StrOrString<'static>, app_version: StrOrString<'static>) -> Self {
@@ -36,6 +63,23 @@ impl HttpServerBuilder {
             app_name: app_name.to_string(),
             app_version: app_version.to_string(),
             custom_middlewares: vec![],
+            drain_middleware: None,
+            probes_middleware: None,
+            health_check_middleware: None,
+            version_middleware: None,
+            #[cfg(feature = "http-tls")]
+            tls_certificate: None,
+            #[cfg(feature = "http-tls")]
+            tls_client_ca_path: None,
+            protocol: HttpProtocol::Http1,
+            #[cfg(feature = "http-tls")]
+            trust_forwarded_headers: false,
+            #[cfg(feature = "http-tls")]
+            front_listener: None,
+            metrics_routes: vec![],
+            max_metrics_path_label_values: DEFAULT_MAX_PATH_LABEL_VALUES,
+            metrics_endpoint_enabled: true,
+            access_log_config: None,
         }
     }
 
@@ -61,6 +105,22 @@ impl HttpServerBuilder {
         }
     }
 
+    pub fn set_drain_middleware(&mut self, middleware: DrainMiddleware) {
+        self.drain_middleware = Some(Arc::new(middleware));
+    }
+
+    pub fn set_probes_middleware(&mut self, middleware: ProbesMiddleware) {
+        self.probes_middleware = Some(Arc::new(middleware));
+    }
+
+    pub fn set_health_check_middleware(&mut self, middleware: HealthCheckMiddleware) {
+        self.health_check_middleware = Some(Arc::new(middleware));
+    }
+
+    pub fn set_version_middleware(&mut self, middleware: VersionMiddleware) {
+        self.version_middleware = Some(Arc::new(middleware));
+    }
+
     pub fn register_custom_middleware(
         &mut self,
         middleware: Arc<dyn HttpServerMiddleware + Send + Sync + 'static>,
@@ -72,6 +132,75 @@ impl HttpServerBuilder {
         self.listen_address = SocketAddr::new(ip, port);
     }
 
+    // Certificate files are watched and reloaded when they are changed
+    #[cfg(feature = "http-tls")]
+    pub fn with_tls(&mut self, cert_path: impl Into<String>, key_path: impl Into<String>) {
+        self.tls_certificate = Some(TlsCertificateSource::from_files(cert_path, key_path));
+    }
+
+    #[cfg(feature = "http-tls")]
+    pub fn with_tls_pem(&mut self, cert_pem: impl Into<String>, key_pem: impl Into<String>) {
+        self.tls_certificate = Some(TlsCertificateSource::from_pem(cert_pem, key_pem));
+    }
+
+    // Clients have to present a certificate signed by this CA
+    #[cfg(feature = "http-tls")]
+    pub fn with_client_ca(&mut self, client_ca_path: impl Into<String>) {
+        self.tls_client_ca_path = Some(client_ca_path.into());
+    }
+
+    #[cfg(feature = "http-tls")]
+    fn get_tls_config(&mut self) -> Result<Option<TlsConfig>, String> {
+        let Some(certificate) = self.tls_certificate.take() else {
+            if self.tls_client_ca_path.is_some() {
+                return Err(
+                    "Client CA is set for HTTP server, but server certificate is not".to_string(),
+                );
+            }
+
+            return Ok(None);
+        };
+
+        let mut tls_config = TlsConfig::new(certificate);
+        tls_config.client_ca_path = self.tls_client_ca_path.take();
+
+        Ok(Some(tls_config))
+    }
+
+    // Can be overridden with HTTP_PROTOCOL (http1, http2, auto) or legacy HTTP2 env variable
+    pub fn set_protocol(&mut self, protocol: HttpProtocol) -> &mut Self {
+        self.protocol = protocol;
+        self
+    }
+
+    // With TLS or Auto protocol requests come to MyHttpServer through HttpFrontListener which
+    // replaces X-Forwarded-For and X-Forwarded-Proto sent by clients. Set when the service is
+    // behind a load balancer, so the headers it sets are kept
+    #[cfg(feature = "http-tls")]
+    pub fn trust_forwarded_headers(&mut self) -> &mut Self {
+        self.trust_forwarded_headers = true;
+        self
+    }
+
+    // Paths which do not match any controller route are used as `path` metric label as is.
+    // After the limit is reached they are written as `other`
+    pub fn set_max_metrics_path_label_values(&mut self, max_path_label_values: usize) -> &mut Self {
+        self.max_metrics_path_label_values = max_path_label_values;
+        self
+    }
+
+    // Used when /metrics is served by the admin server
+    pub fn disable_metrics_endpoint(&mut self) -> &mut Self {
+        self.metrics_endpoint_enabled = false;
+        self
+    }
+
+    // SDK endpoints (isalive, probes, metrics) are not written to access log
+    pub fn enable_access_log(&mut self, config: AccessLogConfig) -> &mut Self {
+        self.access_log_config = Some(config);
+        self
+    }
+
     pub fn add_auth_middleware(
         &mut self,
         middleware: Arc<dyn HttpServerMiddleware + Send + Sync + 'static>,
@@ -87,6 +216,8 @@ impl HttpServerBuilder {
         if self.controllers.is_none() {
             self.controllers = Some(ControllersMiddleware::new(None, None));
         }
+        self.metrics_routes
+            .push(("GET", action.get_route().to_string()));
         self.controllers
             .as_mut()
             .unwrap()
@@ -101,6 +232,8 @@ impl HttpServerBuilder {
         if self.controllers.is_none() {
             self.controllers = Some(ControllersMiddleware::new(None, None));
         }
+        self.metrics_routes
+            .push(("POST", action.get_route().to_string()));
         self.controllers
             .as_mut()
             .unwrap()
@@ -115,6 +248,8 @@ impl HttpServerBuilder {
         if self.controllers.is_none() {
             self.controllers = Some(ControllersMiddleware::new(None, None));
         }
+        self.metrics_routes
+            .push(("PUT", action.get_route().to_string()));
         self.controllers
             .as_mut()
             .unwrap()
@@ -129,6 +264,8 @@ impl HttpServerBuilder {
         if self.controllers.is_none() {
             self.controllers = Some(ControllersMiddleware::new(None, None));
         }
+        self.metrics_routes
+            .push(("DELETE", action.get_route().to_string()));
         self.controllers
             .as_mut()
             .unwrap()
@@ -136,15 +273,135 @@ impl HttpServerBuilder {
         return self;
     }
 
+    // MyHttpServer listens on the public address itself when it can. With TLS or Auto protocol
+    // it listens on loopback and HttpFrontListener, taken with take_front_listener, has to be
+    // started to take the public address. Server is not started
     pub fn build(&mut self) -> MyHttpServer {
-        let mut my_http_server = MyHttpServer::new(self.listen_address);
+        self.protocol = HttpProtocol::resolve_from_env(self.protocol);
+        let middlewares = self.build_middlewares();
+
+        #[cfg(feature = "http-tls")]
+        {
+            self.build_with_front_listener(middlewares)
+        }
+
+        #[cfg(not(feature = "http-tls"))]
+        {
+            create_http_server(self.listen_address, &middlewares)
+        }
+    }
+
+    // Every request makes one more hop through the loopback, so it is used only when needed
+    #[cfg(feature = "http-tls")]
+    fn build_with_front_listener(
+        &mut self,
+        mut middlewares: Vec<Arc<dyn HttpServerMiddleware + Send + Sync + 'static>>,
+    ) -> MyHttpServer {
+        let tls_config = self
+            .get_tls_config()
+            .unwrap_or_else(|err| panic!("Can not build HTTP server. {}", err));
+
+        if self.protocol != HttpProtocol::Auto && tls_config.is_none() {
+            return create_http_server(self.listen_address, &middlewares);
+        }
+
+        let token = FrontListenerToken::new();
+        middlewares.insert(
+            0,
+            Arc::new(FrontListenerGuardMiddleware::new(token.clone())),
+        );
+
+        let upstream = crate::reserve_loopback_address()
+            .unwrap_or_else(|err| panic!("Can not build HTTP server. {}", err));
+        let mut front_listener =
+            HttpFrontListener::new(self.listen_address, upstream, self.protocol, token);
+        front_listener.set_trust_forwarded_headers(self.trust_forwarded_headers);
+
+        if let Some(tls_config) = tls_config {
+            front_listener.set_tls_config(tls_config);
+        }
+
+        self.front_listener = Some(front_listener);
+
+        create_http_server(upstream, &middlewares)
+    }
+
+    // Resolved from the environment by build. MyHttpServer is started with start_h2 for Http2
+    pub fn get_protocol(&self) -> HttpProtocol {
+        self.protocol
+    }
+
+    #[cfg(feature = "http-tls")]
+    pub fn take_front_listener(&mut self) -> Option<HttpFrontListener> {
+        self.front_listener.take()
+    }
+
+    // Builds and starts the server and the front listener if it is needed
+    pub async fn start(&mut self, app_states: Arc<AppStates>) -> Result<MyHttpServer, String> {
+        let mut http_server = self.build();
+
+        if self.get_protocol() == HttpProtocol::Http2 {
+            http_server.start_h2(app_states.clone(), my_logger::LOGGER.clone());
+        } else {
+            http_server.start(app_states.clone(), my_logger::LOGGER.clone());
+        }
+
+        #[cfg(feature = "http-tls")]
+        if let Some(front_listener) = self.take_front_listener() {
+            front_listener.start(app_states).await?;
+        }
+
+        Ok(http_server)
+    }
+
+    fn build_middlewares(&mut self) -> Vec<Arc<dyn HttpServerMiddleware + Send + Sync + 'static>> {
+        let mut middlewares: Vec<Arc<dyn HttpServerMiddleware + Send + Sync + 'static>> = vec![];
+
+        // First, so everything below is handled with request id
+        middlewares.push(Arc::new(RequestIdMiddleware::new()));
 
         let is_alive = IsAliveMiddleware::new(self.app_name.clone(), self.app_version.clone());
-        my_http_server.add_middleware(Arc::new(is_alive));
-        my_http_server.add_middleware(Arc::new(MetricsMiddleware{}));
+        middlewares.push(Arc::new(is_alive));
+
+        if let Some(probes_middleware) = self.probes_middleware.take() {
+            middlewares.push(probes_middleware);
+        }
+
+        if let Some(health_check_middleware) = self.health_check_middleware.take() {
+            middlewares.push(health_check_middleware);
+        }
+
+        if let Some(version_middleware) = self.version_middleware.take() {
+            middlewares.push(version_middleware);
+        }
+
+        if self.metrics_endpoint_enabled {
+            middlewares.push(Arc::new(MetricsEndpointMiddleware::new()));
+        }
+
+        // Built once, when all the routes are registered
+        let metrics_paths = Arc::new(HttpMetricsPaths::new(
+            self.metrics_routes
+                .iter()
+                .map(|(method, route)| (*method, route.as_str())),
+            self.max_metrics_path_label_values,
+        ));
+
+        middlewares.push(Arc::new(MetricsMiddleware::new(metrics_paths.clone())));
+
+        if let Some(access_log_config) = self.access_log_config.take() {
+            middlewares.push(Arc::new(AccessLogMiddleware::new(
+                access_log_config,
+                metrics_paths,
+            )));
+        }
+
+        if let Some(drain_middleware) = self.drain_middleware.take() {
+            middlewares.push(drain_middleware);
+        }
 
         for middleware in self.custom_middlewares.drain(..) {
-            my_http_server.add_middleware(middleware);
+            middlewares.push(middleware);
         }
 
         if let Some(controllers) = self.controllers.take() {
@@ -155,14 +412,59 @@ impl HttpServerBuilder {
                 self.app_version.clone(),
             );
 
-            my_http_server.add_middleware(Arc::new(swagger_middleware));
+            middlewares.push(Arc::new(swagger_middleware));
 
             if let Some(auth_middleware) = self.auth_middleware.take() {
-                my_http_server.add_middleware(auth_middleware);
+                middlewares.push(auth_middleware);
             }
-            my_http_server.add_middleware(controllers.clone());
+            middlewares.push(controllers.clone());
         }
 
-        my_http_server
+        middlewares
+    }
+}
+
+fn create_http_server(
+    listen_address: SocketAddr,
+    middlewares: &[Arc<dyn HttpServerMiddleware + Send + Sync + 'static>],
+) -> MyHttpServer {
+    let mut my_http_server = MyHttpServer::new(listen_address);
+
+    for middleware in middlewares {
+        my_http_server.add_middleware(middleware.clone());
+    }
+
+    my_http_server
+}
+
+#[cfg(test)]
+mod tests {
+    use my_http_server::{HttpContext, HttpRequest, HttpServerRequestFlow};
+
+    use super::HttpServerBuilder;
+    use crate::METRICS_PATH;
+
+    // Status code of the response to GET /metrics
+    async fn get_metrics(builder: &mut HttpServerBuilder) -> u16 {
+        let mut ctx = HttpContext::new(HttpRequest::new(
+            "GET",
+            METRICS_PATH,
+            hyper::HeaderMap::new(),
+        ));
+        let mut flow = HttpServerRequestFlow::new(builder.build_middlewares());
+
+        crate::get_status_code(&flow.next(&mut ctx).await)
+    }
+
+    #[tokio::test]
+    async fn test_public_server_does_not_serve_metrics_with_admin_server() {
+        let mut builder = HttpServerBuilder::new("test-service".into(), "1.0.0".into());
+        assert_eq!(get_metrics(&mut builder).await, 200);
+
+        // The same as ServiceContext::enable_admin_server does
+        let mut builder = HttpServerBuilder::new("test-service".into(), "1.0.0".into());
+        builder.disable_metrics_endpoint();
+
+        assert_eq!(get_metrics(&mut builder).await, 404);
     }
 }
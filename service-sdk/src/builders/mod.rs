@@ -1,7 +1,31 @@
 pub mod consts;
+mod admin_server_builder;
+pub use admin_server_builder::*;
 mod http_server_builder;
 pub use http_server_builder::*;
+mod http_protocol;
+pub use http_protocol::*;
+#[cfg(feature = "http-tls")]
+mod http_front_listener;
+#[cfg(feature = "http-tls")]
+pub use http_front_listener::*;
+#[cfg(feature = "http-tls")]
+mod front_listener_guard;
+#[cfg(feature = "http-tls")]
+pub use front_listener_guard::*;
+#[cfg(any(feature = "http-tls", feature = "grpc-with-tls"))]
+mod accept_error_backoff;
+#[cfg(any(feature = "http-tls", feature = "grpc-with-tls"))]
+pub use accept_error_backoff::*;
 #[cfg(feature = "grpc")]
 mod grpc_server_builder;
 #[cfg(feature = "grpc")]
 pub use grpc_server_builder::*;
+#[cfg(feature = "grpc")]
+mod grpc_server_error;
+#[cfg(feature = "grpc")]
+pub use grpc_server_error::*;
+#[cfg(feature = "grpc-with-tls")]
+mod grpc_tls_incoming;
+#[cfg(feature = "grpc-with-tls")]
+pub use grpc_tls_incoming::*;
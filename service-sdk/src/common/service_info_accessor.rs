@@ -1,6 +1,25 @@
+use async_trait::async_trait;
 use rust_extensions::StrOrString;
 
+// Log settings have defaults, so readers implemented by hand need only the name and version.
+// SdkSettingsTraits reads them from log_level, process_log_levels and log_output fields of the model
+#[async_trait]
 pub trait ServiceInfo {
     fn get_service_name(&self) -> StrOrString<'static>;
     fn get_service_version(&self) -> StrOrString<'static>;
+
+    // debug, info, warning, error, fatal
+    async fn get_log_level(&self) -> Option<String> {
+        None
+    }
+
+    // process1=debug;process2=warning
+    async fn get_process_log_levels(&self) -> Option<String> {
+        None
+    }
+
+    // seq,json
+    async fn get_log_output(&self) -> Option<String> {
+        None
+    }
 }
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+use rust_extensions::AppStates;
+
+use crate::{InFlightRequestGuard, InFlightRequests};
+
+pub struct DrainMiddleware {
+    app_states: Arc<AppStates>,
+    in_flight: Arc<InFlightRequests>,
+}
+
+impl DrainMiddleware {
+    pub fn new(app_states: Arc<AppStates>, in_flight: Arc<InFlightRequests>) -> Self {
+        Self {
+            app_states,
+            in_flight,
+        }
+    }
+
+    // None if the service is shutting down and the request has to be rejected
+    fn try_start_request(&self) -> Option<InFlightRequestGuard<'_>> {
+        if self.app_states.is_shutting_down() {
+            return None;
+        }
+
+        Some(self.in_flight.start_request())
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for DrainMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        let Some(_guard) = self.try_start_request() else {
+            return HttpOutput::as_text("Service is shutting down".to_string())
+                .into_fail_result(503, false);
+        };
+
+        get_next.next(ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rust_extensions::AppStates;
+
+    use super::DrainMiddleware;
+    use crate::InFlightRequests;
+
+    #[test]
+    fn requests_are_counted_until_shutdown() {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        let in_flight = Arc::new(InFlightRequests::new());
+        let middleware = DrainMiddleware::new(app_states.clone(), in_flight.clone());
+
+        let guard = middleware.try_start_request();
+        assert!(guard.is_some());
+        assert_eq!(in_flight.get_count(), 1);
+
+        app_states.set_shutting_down();
+
+        assert!(middleware.try_start_request().is_none());
+        assert_eq!(in_flight.get_count(), 1);
+
+        drop(guard);
+        assert_eq!(in_flight.get_count(), 0);
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+pub struct InFlightRequests {
+    count: AtomicUsize,
+}
+
+impl InFlightRequests {
+    pub fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    pub fn start_request(&self) -> InFlightRequestGuard<'_> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        InFlightRequestGuard { owner: self }
+    }
+
+    // Returns false if requests are still running when the timeout expires
+    pub async fn wait_until_drained(&self, timeout: Duration) -> bool {
+        let started = std::time::Instant::now();
+
+        while self.get_count() > 0 {
+            if started.elapsed() >= timeout {
+                return false;
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        true
+    }
+}
+
+pub struct InFlightRequestGuard<'s> {
+    owner: &'s InFlightRequests,
+}
+
+impl<'s> Drop for InFlightRequestGuard<'s> {
+    fn drop(&mut self) {
+        self.owner.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::InFlightRequests;
+
+    #[test]
+    fn guard_decrements_count_on_drop() {
+        let in_flight = InFlightRequests::new();
+
+        let first = in_flight.start_request();
+        let second = in_flight.start_request();
+        assert_eq!(in_flight.get_count(), 2);
+
+        drop(first);
+        assert_eq!(in_flight.get_count(), 1);
+
+        drop(second);
+        assert_eq!(in_flight.get_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn wait_until_drained_returns_when_requests_finish() {
+        let in_flight = Arc::new(InFlightRequests::new());
+
+        {
+            let in_flight = in_flight.clone();
+            tokio::spawn(async move {
+                let _guard = in_flight.start_request();
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            });
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(in_flight.get_count(), 1);
+
+        assert!(in_flight.wait_until_drained(Duration::from_secs(5)).await);
+    }
+
+    #[tokio::test]
+    async fn wait_until_drained_gives_up_after_timeout() {
+        let in_flight = InFlightRequests::new();
+        let _guard = in_flight.start_request();
+
+        assert!(
+            !in_flight
+                .wait_until_drained(Duration::from_millis(100))
+                .await
+        );
+        assert_eq!(in_flight.get_count(), 1);
+    }
+}
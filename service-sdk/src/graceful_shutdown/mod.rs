@@ -0,0 +1,7 @@
+mod in_flight_requests;
+mod drain_middleware;
+mod shutdown_signal;
+
+pub use in_flight_requests::*;
+pub use drain_middleware::*;
+pub use shutdown_signal::*;
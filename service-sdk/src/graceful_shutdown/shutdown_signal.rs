@@ -0,0 +1,115 @@
+use std::{sync::Arc, time::Duration};
+
+use my_logger::LogEventCtx;
+use rust_extensions::AppStates;
+
+use crate::ReadinessProbe;
+
+pub fn spawn_shutdown_signal_handler(
+    app_states: Arc<AppStates>,
+    readiness_probe: Arc<ReadinessProbe>,
+    pre_drain_delay: Duration,
+) {
+    tokio::spawn(async move {
+        let signal_name = wait_for_shutdown_signal().await;
+
+        // Ctrl+C comes from a developer, nobody routes traffic to the process in that case
+        let pre_drain_delay = if signal_name == "SIGTERM" {
+            pre_drain_delay
+        } else {
+            Duration::ZERO
+        };
+
+        my_logger::LOGGER.write_info(
+            "Shutdown".to_string(),
+            format!(
+                "Received {}. Shutting down the application in {:?}",
+                signal_name, pre_drain_delay
+            ),
+            LogEventCtx::new(),
+        );
+
+        begin_shutdown(&app_states, &readiness_probe, pre_drain_delay).await;
+    });
+}
+
+// Readiness fails first, so Kubernetes removes the pod from the endpoints while it still
+// serves requests. New requests are rejected only when the delay is over
+pub async fn begin_shutdown(
+    app_states: &AppStates,
+    readiness_probe: &ReadinessProbe,
+    pre_drain_delay: Duration,
+) {
+    readiness_probe.set_draining();
+
+    if !pre_drain_delay.is_zero() {
+        tokio::time::sleep(pre_drain_delay).await;
+    }
+
+    app_states.set_shutting_down();
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = signal(SignalKind::terminate()).expect("Failed to bind SIGTERM handler");
+
+    tokio::select! {
+        _ = sigterm.recv() => "SIGTERM",
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() -> &'static str {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("Failed to bind Ctrl+C handler");
+    "Ctrl+C"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use rust_extensions::AppStates;
+
+    use super::begin_shutdown;
+    use crate::ReadinessProbe;
+
+    #[tokio::test]
+    async fn readiness_fails_before_requests_are_rejected() {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        app_states.set_initialized();
+        let readiness_probe = Arc::new(ReadinessProbe::new(app_states.clone()));
+
+        let handle = {
+            let app_states = app_states.clone();
+            let readiness_probe = readiness_probe.clone();
+            tokio::spawn(async move {
+                begin_shutdown(&app_states, &readiness_probe, Duration::from_millis(200)).await;
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(readiness_probe.get_not_ready().await, vec!["shutdown"]);
+        assert!(!app_states.is_shutting_down());
+
+        handle.await.unwrap();
+
+        assert!(app_states.is_shutting_down());
+    }
+
+    #[tokio::test]
+    async fn zero_delay_rejects_requests_right_away() {
+        let app_states = AppStates::create_un_initialized();
+        let readiness_probe = ReadinessProbe::new(Arc::new(AppStates::create_un_initialized()));
+
+        begin_shutdown(&app_states, &readiness_probe, Duration::ZERO).await;
+
+        assert!(readiness_probe.is_draining());
+        assert!(app_states.is_shutting_down());
+    }
+}
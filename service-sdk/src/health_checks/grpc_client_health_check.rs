@@ -0,0 +1,82 @@
+use std::sync::OnceLock;
+
+use my_grpc_extensions::tonic::transport::{Channel, Endpoint};
+use tonic_health::pb::{
+    health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest,
+};
+
+use crate::{HealthCheck, HealthStatus};
+
+// Calls grpc.health.v1/Check on one channel which is kept between the checks. Pass the channel
+// of the grpc client with from_channel to check the connection the service actually uses
+pub struct GrpcClientHealthCheck {
+    name: String,
+    endpoint: Result<Endpoint, String>,
+    channel: OnceLock<Channel>,
+    // Empty is the status of the whole server
+    service: String,
+}
+
+impl GrpcClientHealthCheck {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            endpoint: Endpoint::from_shared(url.into())
+                .map_err(|err| format!("Invalid url: {}", err)),
+            channel: OnceLock::new(),
+            service: String::new(),
+        }
+    }
+
+    pub fn from_channel(name: impl Into<String>, channel: Channel) -> Self {
+        Self {
+            name: name.into(),
+            endpoint: Err("Endpoint is not used when the channel is set".to_string()),
+            channel: OnceLock::from(channel),
+            service: String::new(),
+        }
+    }
+
+    // Service name as it is registered in the health service of the server, e.g. accounts.AccountsService
+    pub fn with_service(mut self, service: impl Into<String>) -> Self {
+        self.service = service.into();
+        self
+    }
+
+    // Lazy channel connects on the first call and reconnects by itself, so it is created once.
+    // It has to be created inside tokio runtime, so it is not done in new
+    fn get_channel(&self) -> Result<&Channel, String> {
+        if let Some(channel) = self.channel.get() {
+            return Ok(channel);
+        }
+
+        let endpoint = self.endpoint.as_ref().map_err(|err| err.to_string())?;
+        Ok(self.channel.get_or_init(|| endpoint.connect_lazy()))
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for GrpcClientHealthCheck {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> HealthStatus {
+        let channel = match self.get_channel() {
+            Ok(channel) => channel.clone(),
+            Err(err) => return HealthStatus::unhealthy(err),
+        };
+
+        let request = HealthCheckRequest {
+            service: self.service.clone(),
+        };
+
+        match HealthClient::new(channel).check(request).await {
+            Ok(response) => match response.into_inner().status() {
+                ServingStatus::Serving => HealthStatus::healthy(),
+                status => HealthStatus::unhealthy(format!("{:?}", status)),
+            },
+            Err(status) => HealthStatus::unhealthy(status.to_string()),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::{sync::Arc, time::Duration};
+
+use rust_extensions::AppStates;
+use tonic_health::{server::HealthReporter, ServingStatus};
+
+use crate::{HealthCheckRegistry, ReadinessProbe};
+
+const GRPC_HEALTH_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+// Overall server status is reported under the empty service name as grpc.health.v1 requires
+const SERVER_SERVICE_NAME: &str = "";
+
+pub struct GrpcHealthStatusUpdater {
+    reporter: HealthReporter,
+    service_names: Vec<&'static str>,
+    app_states: Arc<AppStates>,
+    readiness_probe: Arc<ReadinessProbe>,
+    health_check_registry: Option<Arc<HealthCheckRegistry>>,
+    last_status: Option<ServingStatus>,
+}
+
+impl GrpcHealthStatusUpdater {
+    pub fn new(
+        reporter: HealthReporter,
+        service_names: Vec<&'static str>,
+        app_states: Arc<AppStates>,
+        readiness_probe: Arc<ReadinessProbe>,
+        health_check_registry: Option<Arc<HealthCheckRegistry>>,
+    ) -> Self {
+        Self {
+            reporter,
+            service_names,
+            app_states,
+            readiness_probe,
+            health_check_registry,
+            last_status: None,
+        }
+    }
+
+    pub fn start(mut self) {
+        tokio::spawn(async move {
+            loop {
+                self.refresh().await;
+
+                if self.app_states.is_shutting_down() {
+                    break;
+                }
+
+                // Draining is pushed at once, so balancers stop sending calls during the pre-drain delay
+                let readiness_probe = self.readiness_probe.clone();
+
+                tokio::select! {
+                    _ = tokio::time::sleep(GRPC_HEALTH_REFRESH_INTERVAL) => {}
+                    _ = self.app_states.wait_until_shutdown() => {}
+                    _ = readiness_probe.wait_until_draining(), if !readiness_probe.is_draining() => {}
+                }
+            }
+        });
+    }
+
+    async fn get_status(&self) -> ServingStatus {
+        if !self.app_states.is_initialized()
+            || self.readiness_probe.is_draining()
+            || self.app_states.is_shutting_down()
+        {
+            return ServingStatus::NotServing;
+        }
+
+        if let Some(health_check_registry) = self.health_check_registry.as_ref() {
+            if !health_check_registry.get_report().await.healthy {
+                return ServingStatus::NotServing;
+            }
+        }
+
+        ServingStatus::Serving
+    }
+
+    // Watch streams get an update only when the status is actually changed
+    async fn refresh(&mut self) {
+        let status = self.get_status().await;
+
+        if self.last_status == Some(status) {
+            return;
+        }
+
+        self.reporter
+            .set_service_status(SERVER_SERVICE_NAME, status)
+            .await;
+
+        for service_name in self.service_names.iter() {
+            self.reporter.set_service_status(service_name, status).await;
+        }
+
+        self.last_status = Some(status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use my_grpc_extensions::tonic::transport::{server::TcpIncoming, Channel, Server};
+    use rust_extensions::AppStates;
+    use tonic_health::pb::{
+        health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest,
+    };
+
+    use super::GrpcHealthStatusUpdater;
+    use crate::ReadinessProbe;
+
+    async fn check(client: &mut HealthClient<Channel>, service: &str) -> ServingStatus {
+        let request = HealthCheckRequest {
+            service: service.to_string(),
+        };
+
+        client.check(request).await.unwrap().into_inner().status()
+    }
+
+    #[tokio::test]
+    async fn not_serving_is_pushed_as_soon_as_draining_starts() {
+        let app_states = Arc::new(AppStates::create_initialized());
+        let readiness_probe = Arc::new(ReadinessProbe::new(app_states.clone()));
+        let (reporter, health_service) = tonic_health::server::health_reporter();
+
+        GrpcHealthStatusUpdater::new(
+            reporter,
+            vec!["test.TestService"],
+            app_states.clone(),
+            readiness_probe.clone(),
+            None,
+        )
+        .start();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+
+        tokio::spawn(
+            Server::builder()
+                .add_service(health_service)
+                .serve_with_incoming(incoming),
+        );
+
+        let channel = Channel::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = HealthClient::new(channel);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(check(&mut client, "").await, ServingStatus::Serving);
+        assert_eq!(
+            check(&mut client, "test.TestService").await,
+            ServingStatus::Serving
+        );
+
+        readiness_probe.set_draining();
+
+        // Well before the refresh interval
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(check(&mut client, "").await, ServingStatus::NotServing);
+        assert_eq!(
+            check(&mut client, "test.TestService").await,
+            ServingStatus::NotServing
+        );
+        assert!(!app_states.is_shutting_down());
+    }
+}
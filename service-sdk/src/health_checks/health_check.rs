@@ -0,0 +1,43 @@
+use serde::Serialize;
+
+pub struct HealthStatus {
+    pub is_healthy: bool,
+    pub details: Option<String>,
+}
+
+impl HealthStatus {
+    pub fn healthy() -> Self {
+        Self {
+            is_healthy: true,
+            details: None,
+        }
+    }
+
+    pub fn unhealthy(details: impl Into<String>) -> Self {
+        Self {
+            is_healthy: false,
+            details: Some(details.into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait HealthCheck {
+    fn get_name(&self) -> &str;
+    async fn check(&self) -> HealthStatus;
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HealthCheckResult {
+    pub name: String,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub checks: Vec<HealthCheckResult>,
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+
+use crate::HealthCheckRegistry;
+
+pub const HEALTH_CHECK_PATH: &str = "/api/health";
+
+pub struct HealthCheckMiddleware {
+    health_check_registry: Arc<HealthCheckRegistry>,
+}
+
+impl HealthCheckMiddleware {
+    pub fn new(health_check_registry: Arc<HealthCheckRegistry>) -> Self {
+        Self {
+            health_check_registry,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for HealthCheckMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        if ctx.request.http_path.as_str() != HEALTH_CHECK_PATH {
+            return get_next.next(ctx).await;
+        }
+
+        let report = self.health_check_registry.get_report().await;
+
+        if report.healthy {
+            return HttpOutput::as_json(report).into_ok_result(false);
+        }
+
+        HttpOutput::as_json(report).into_fail_result(503, false)
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{HealthCheck, HealthCheckResult, HealthReport};
+
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct HealthCheckRegistry {
+    checks: Mutex<Vec<Arc<dyn HealthCheck + Send + Sync + 'static>>>,
+    check_timeout: Mutex<Duration>,
+}
+
+impl HealthCheckRegistry {
+    pub fn new() -> Self {
+        Self {
+            checks: Mutex::new(vec![]),
+            check_timeout: Mutex::new(DEFAULT_HEALTH_CHECK_TIMEOUT),
+        }
+    }
+
+    pub fn register_check(&self, check: Arc<dyn HealthCheck + Send + Sync + 'static>) {
+        self.checks.lock().unwrap().push(check);
+    }
+
+    pub fn set_check_timeout(&self, timeout: Duration) {
+        *self.check_timeout.lock().unwrap() = timeout;
+    }
+
+    // Runs all the checks concurrently and refreshes service_health_check_status gauges
+    pub async fn get_report(&self) -> HealthReport {
+        let checks = self.checks.lock().unwrap().clone();
+        let check_timeout = *self.check_timeout.lock().unwrap();
+
+        let mut handles = Vec::with_capacity(checks.len());
+
+        for check in checks {
+            let name = check.get_name().to_string();
+            handles.push((
+                name,
+                tokio::spawn(async move { run_check(check, check_timeout).await }),
+            ));
+        }
+
+        let mut results = Vec::with_capacity(handles.len());
+
+        for (name, handle) in handles {
+            match handle.await {
+                Ok(result) => results.push(result),
+                Err(err) => results.push(HealthCheckResult {
+                    name,
+                    healthy: false,
+                    latency_ms: 0,
+                    details: Some(format!("Health check panicked: {}", err)),
+                }),
+            }
+        }
+
+        for result in results.iter() {
+            metrics::gauge!(
+                "service_health_check_status",
+                &[("check", result.name.clone())]
+            )
+            .set(if result.healthy { 1.0 } else { 0.0 });
+        }
+
+        HealthReport {
+            healthy: results.iter().all(|itm| itm.healthy),
+            checks: results,
+        }
+    }
+}
+
+async fn run_check(
+    check: Arc<dyn HealthCheck + Send + Sync + 'static>,
+    check_timeout: Duration,
+) -> HealthCheckResult {
+    let mut sw = stopwatch::Stopwatch::start_new();
+    let status = tokio::time::timeout(check_timeout, check.check()).await;
+    sw.stop();
+
+    let latency_ms = sw.elapsed().as_millis() as u64;
+
+    match status {
+        Ok(status) => HealthCheckResult {
+            name: check.get_name().to_string(),
+            healthy: status.is_healthy,
+            latency_ms,
+            details: status.details,
+        },
+        Err(_) => HealthCheckResult {
+            name: check.get_name().to_string(),
+            healthy: false,
+            latency_ms,
+            details: Some(format!("Timeout after {:?}", check_timeout)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::HealthCheckRegistry;
+    use crate::{HealthCheck, HealthStatus};
+
+    enum TestBehavior {
+        Healthy,
+        Unhealthy,
+        Hang,
+        Panic,
+    }
+
+    struct TestCheck {
+        name: &'static str,
+        behavior: TestBehavior,
+    }
+
+    #[async_trait::async_trait]
+    impl HealthCheck for TestCheck {
+        fn get_name(&self) -> &str {
+            self.name
+        }
+
+        async fn check(&self) -> HealthStatus {
+            match self.behavior {
+                TestBehavior::Healthy => HealthStatus::healthy(),
+                TestBehavior::Unhealthy => HealthStatus::unhealthy("down"),
+                TestBehavior::Hang => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    HealthStatus::healthy()
+                }
+                TestBehavior::Panic => panic!("check failed"),
+            }
+        }
+    }
+
+    fn register(registry: &HealthCheckRegistry, name: &'static str, behavior: TestBehavior) {
+        registry.register_check(Arc::new(TestCheck { name, behavior }));
+    }
+
+    #[tokio::test]
+    async fn test_empty_registry_is_healthy() {
+        let registry = HealthCheckRegistry::new();
+
+        let report = registry.get_report().await;
+
+        assert!(report.healthy);
+        assert!(report.checks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_all_healthy() {
+        let registry = HealthCheckRegistry::new();
+        register(&registry, "a", TestBehavior::Healthy);
+        register(&registry, "b", TestBehavior::Healthy);
+
+        let report = registry.get_report().await;
+
+        assert!(report.healthy);
+        assert_eq!(report.checks.len(), 2);
+        assert_eq!(report.checks[0].name, "a");
+        assert_eq!(report.checks[1].name, "b");
+    }
+
+    #[tokio::test]
+    async fn test_one_unhealthy_fails_report() {
+        let registry = HealthCheckRegistry::new();
+        register(&registry, "a", TestBehavior::Healthy);
+        register(&registry, "b", TestBehavior::Unhealthy);
+
+        let report = registry.get_report().await;
+
+        assert!(!report.healthy);
+        assert!(report.checks[0].healthy);
+        assert!(!report.checks[1].healthy);
+        assert_eq!(report.checks[1].details.as_deref(), Some("down"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_marks_check_unhealthy() {
+        let registry = HealthCheckRegistry::new();
+        registry.set_check_timeout(Duration::from_millis(100));
+        register(&registry, "slow", TestBehavior::Hang);
+
+        let report = registry.get_report().await;
+
+        assert!(!report.healthy);
+        assert_eq!(report.checks[0].name, "slow");
+        assert!(report.checks[0]
+            .details
+            .as_ref()
+            .unwrap()
+            .starts_with("Timeout"));
+    }
+
+    #[tokio::test]
+    async fn test_panicked_check_keeps_name() {
+        let registry = HealthCheckRegistry::new();
+        register(&registry, "broken", TestBehavior::Panic);
+        register(&registry, "ok", TestBehavior::Healthy);
+
+        let report = registry.get_report().await;
+
+        assert!(!report.healthy);
+        assert_eq!(report.checks[0].name, "broken");
+        assert!(!report.checks[0].healthy);
+        assert!(report.checks[1].healthy);
+    }
+}
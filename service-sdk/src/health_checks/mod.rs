@@ -0,0 +1,29 @@
+mod health_check;
+mod health_check_registry;
+mod health_check_middleware;
+#[cfg(feature = "my-service-bus")]
+mod sb_health_check;
+#[cfg(feature = "my-nosql-data-reader-sdk")]
+mod no_sql_health_check;
+#[cfg(feature = "postgres")]
+mod postgres_health_check;
+#[cfg(feature = "postgres")]
+mod postgres_tls_config;
+#[cfg(feature = "grpc")]
+mod grpc_client_health_check;
+#[cfg(feature = "grpc")]
+mod grpc_health_service;
+
+pub use health_check::*;
+pub use health_check_registry::*;
+pub use health_check_middleware::*;
+#[cfg(feature = "my-service-bus")]
+pub use sb_health_check::*;
+#[cfg(feature = "my-nosql-data-reader-sdk")]
+pub use no_sql_health_check::*;
+#[cfg(feature = "postgres")]
+pub use postgres_health_check::*;
+#[cfg(feature = "grpc")]
+pub use grpc_client_health_check::*;
+#[cfg(feature = "grpc")]
+pub use grpc_health_service::*;
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use my_no_sql_sdk::reader::MyNoSqlTcpConnection;
+
+use crate::{HealthCheck, HealthStatus};
+
+pub struct NoSqlHealthCheck {
+    connection: Arc<MyNoSqlTcpConnection>,
+}
+
+impl NoSqlHealthCheck {
+    pub fn new(connection: Arc<MyNoSqlTcpConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for NoSqlHealthCheck {
+    fn get_name(&self) -> &str {
+        "my-no-sql-reader"
+    }
+
+    async fn check(&self) -> HealthStatus {
+        if self.connection.is_connected().await {
+            return HealthStatus::healthy();
+        }
+
+        HealthStatus::unhealthy("Not connected to MyNoSql server")
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use my_postgres::PostgresSettings;
+use rustls::{ClientConfig, RootCertStore};
+use tokio::sync::Mutex;
+use tokio_postgres::{config::SslMode, Client, Config, NoTls};
+
+use crate::{HealthCheck, HealthStatus};
+
+// Keeps one connection of its own, separate from the ones of my_postgres, and runs SELECT 1 on it.
+// So it shows the database is reachable and answers. The connection is reopened only when it is
+// closed or the query fails
+pub struct PostgresHealthCheck {
+    name: String,
+    settings: Arc<dyn PostgresSettings + Send + Sync + 'static>,
+    tls_config: ClientConfig,
+    client: Mutex<Option<Client>>,
+}
+
+impl PostgresHealthCheck {
+    pub fn new(
+        name: impl Into<String>,
+        settings: Arc<dyn PostgresSettings + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            settings,
+            tls_config: super::postgres_tls_config::create_postgres_tls_config(None),
+            client: Mutex::new(None),
+        }
+    }
+
+    // Root certificates to verify the server with instead of the system and webpki ones,
+    // e.g. CA of the managed database
+    pub fn with_root_store(mut self, root_store: RootCertStore) -> Self {
+        self.tls_config = super::postgres_tls_config::create_postgres_tls_config(Some(root_store));
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for PostgresHealthCheck {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> HealthStatus {
+        let mut client = self.client.lock().await;
+
+        if client.as_ref().map(|itm| itm.is_closed()).unwrap_or(true) {
+            let conn_string = self.settings.get_connection_string().await;
+
+            match connect(&conn_string, &self.tls_config).await {
+                Ok(connected) => *client = Some(connected),
+                Err(err) => return HealthStatus::unhealthy(err),
+            }
+        }
+
+        match client.as_ref().unwrap().simple_query("SELECT 1").await {
+            Ok(_) => HealthStatus::healthy(),
+            Err(err) => {
+                *client = None;
+                HealthStatus::unhealthy(err.to_string())
+            }
+        }
+    }
+}
+
+async fn connect(conn_string: &str, tls_config: &ClientConfig) -> Result<Client, String> {
+    let config: Config = conn_string
+        .parse()
+        .map_err(|err| format!("Invalid connection string: {}", err))?;
+
+    match config.get_ssl_mode() {
+        SslMode::Disable => {
+            let (client, connection) =
+                config.connect(NoTls).await.map_err(|err| err.to_string())?;
+
+            tokio::spawn(async move {
+                let _ = connection.await;
+            });
+
+            Ok(client)
+        }
+        _ => {
+            let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config.clone());
+
+            let (client, connection) = config.connect(tls).await.map_err(|err| err.to_string())?;
+
+            tokio::spawn(async move {
+                let _ = connection.await;
+            });
+
+            Ok(client)
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use rustls::{ClientConfig, RootCertStore};
+
+// Server certificate is verified as with libpq sslmode=verify-full. System roots and webpki
+// (Mozilla) roots are used unless the caller passes its own root store
+pub(crate) fn create_postgres_tls_config(root_store: Option<RootCertStore>) -> ClientConfig {
+    let root_store = root_store.unwrap_or_else(create_default_root_store);
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .expect("Failed to create TLS config for postgres health check")
+        .with_root_certificates(root_store)
+        .with_no_client_auth()
+}
+
+fn create_default_root_store() -> RootCertStore {
+    let mut root_store = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    let native_certs = rustls_native_certs::load_native_certs();
+
+    for err in native_certs.errors {
+        my_logger::LOGGER.write_warning(
+            "PostgresHealthCheck".to_string(),
+            format!("Can not load system root certificate. {}", err),
+            my_logger::LogEventCtx::new(),
+        );
+    }
+
+    let (_, ignored) = root_store.add_parsable_certificates(native_certs.certs);
+
+    if ignored > 0 {
+        my_logger::LOGGER.write_warning(
+            "PostgresHealthCheck".to_string(),
+            format!("{} system root certificates are ignored", ignored),
+            my_logger::LogEventCtx::new(),
+        );
+    }
+
+    root_store
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use my_service_bus::client::MyServiceBusClient;
+
+use crate::{HealthCheck, HealthStatus};
+
+pub struct SbHealthCheck {
+    sb_client: Arc<MyServiceBusClient>,
+}
+
+impl SbHealthCheck {
+    pub fn new(sb_client: Arc<MyServiceBusClient>) -> Self {
+        Self { sb_client }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for SbHealthCheck {
+    fn get_name(&self) -> &str {
+        "my-service-bus"
+    }
+
+    async fn check(&self) -> HealthStatus {
+        if self.sb_client.is_connected().await {
+            return HealthStatus::healthy();
+        }
+
+        HealthStatus::unhealthy("Not connected to MyServiceBus")
+    }
+}
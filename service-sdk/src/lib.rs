@@ -1,12 +1,38 @@
+mod access_log;
+mod build_info;
 mod builders;
 mod common;
+mod graceful_shutdown;
+mod health_checks;
+mod logging;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod probes;
+mod request_id;
+#[cfg(feature = "my-service-bus")]
+mod service_bus;
 mod service_context;
 mod sdk_metrics;
+#[cfg(any(feature = "grpc-with-tls", feature = "http-tls"))]
+mod tls;
 
 pub use sdk_metrics::*;
+pub use access_log::*;
+pub use build_info::*;
 pub use builders::*;
 pub use common::*;
+pub use graceful_shutdown::*;
+pub use health_checks::*;
+pub use logging::*;
+#[cfg(feature = "opentelemetry")]
+pub use otel::*;
+pub use probes::*;
+pub use request_id::*;
+#[cfg(feature = "my-service-bus")]
+pub use service_bus::*;
 pub use service_context::*;
+#[cfg(any(feature = "grpc-with-tls", feature = "http-tls"))]
+pub use tls::*;
 
 pub extern crate my_http_server;
 
@@ -42,3 +68,6 @@ pub extern crate my_postgres;
 pub extern crate my_service_bus;
 
 pub extern crate metrics;
+
+#[cfg(feature = "opentelemetry")]
+pub extern crate opentelemetry;
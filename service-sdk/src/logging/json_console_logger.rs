@@ -0,0 +1,112 @@
+use std::{collections::HashMap, io::Write, sync::Arc};
+
+use my_logger::{MyLogEvent, MyLoggerReader};
+use serde::Serialize;
+
+use crate::LogLevel;
+
+#[derive(Serialize)]
+struct JsonLogLine<'s> {
+    timestamp: String,
+    level: &'static str,
+    process: &'s str,
+    message: &'s str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'s HashMap<String, String>>,
+    service: &'s str,
+    version: &'s str,
+}
+
+// Writes my_logger events to stdout as JSON lines. Plugged through LogLevelFilterReader
+pub struct JsonConsoleLogger {
+    app_name: String,
+    app_version: String,
+}
+
+impl JsonConsoleLogger {
+    pub fn new(app_name: &str, app_version: &str) -> Self {
+        Self {
+            app_name: app_name.to_string(),
+            app_version: app_version.to_string(),
+        }
+    }
+
+    pub fn serialize(&self, log_event: &MyLogEvent) -> String {
+        let line = JsonLogLine {
+            timestamp: log_event.dt.to_rfc3339(),
+            level: LogLevel::from_my_logger(&log_event.level).as_str(),
+            process: &log_event.process,
+            message: &log_event.message,
+            context: log_event.context.as_ref(),
+            service: &self.app_name,
+            version: &self.app_version,
+        };
+
+        serde_json::to_string(&line).unwrap()
+    }
+}
+
+impl MyLoggerReader for JsonConsoleLogger {
+    fn write_log(&self, log_event: Arc<MyLogEvent>) {
+        let line = self.serialize(&log_event);
+
+        // Whole line under one lock so lines of concurrent events are not interleaved
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use my_logger::MyLogEvent;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::JsonConsoleLogger;
+
+    fn serialize(context: Option<HashMap<String, String>>) -> serde_json::Value {
+        let log_event = MyLogEvent {
+            dt: DateTimeAsMicroseconds::now(),
+            level: my_logger::LogLevel::Error,
+            process: "PaymentsProcessor".to_string(),
+            message: "Timeout".to_string(),
+            context,
+        };
+
+        let line = JsonConsoleLogger::new("payments", "1.0.3").serialize(&log_event);
+        assert!(!line.contains('\n'));
+
+        let mut result: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(result["timestamp"], log_event.dt.to_rfc3339());
+        result.as_object_mut().unwrap().remove("timestamp");
+
+        result
+    }
+
+    #[test]
+    fn test_fields() {
+        let mut context = HashMap::new();
+        context.insert("RequestId".to_string(), "5f0c".to_string());
+
+        assert_eq!(
+            serialize(Some(context)),
+            serde_json::json!({
+                "level": "Error",
+                "process": "PaymentsProcessor",
+                "message": "Timeout",
+                "context": { "RequestId": "5f0c" },
+                "service": "payments",
+                "version": "1.0.3",
+            })
+        );
+    }
+
+    #[test]
+    fn test_context_is_omitted_when_empty() {
+        let result = serialize(None);
+
+        assert!(result.get("context").is_none());
+        assert_eq!(result.as_object().unwrap().len(), 5);
+    }
+}
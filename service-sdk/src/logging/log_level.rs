@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+// Ordered by severity, so level >= min_level means the event is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    FatalError,
+}
+
+impl LogLevel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "debug" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            "fatal" | "fatalerror" | "fatal_error" => Some(Self::FatalError),
+            _ => None,
+        }
+    }
+
+    pub fn from_my_logger(level: &my_logger::LogLevel) -> Self {
+        match level {
+            my_logger::LogLevel::Debug => Self::Debug,
+            my_logger::LogLevel::Info => Self::Info,
+            my_logger::LogLevel::Warning => Self::Warning,
+            my_logger::LogLevel::Error => Self::Error,
+            my_logger::LogLevel::FatalError => Self::FatalError,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Debug => "Debug",
+            Self::Info => "Info",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+            Self::FatalError => "FatalError",
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::LogLevel;
+
+pub const LOG_LEVEL_ENV: &str = "LOG_LEVEL";
+// process1=debug;process2=warning
+pub const PROCESS_LOG_LEVELS_ENV: &str = "PROCESS_LOG_LEVELS";
+pub const MAX_TEMPORARY_LOG_LEVEL_TTL: Duration = Duration::from_secs(60 * 60);
+
+// Applied by SDK log sinks to the events written with my_logger::LOGGER.
+// Configured from ServiceInfo log settings and env variables in ServiceContext::new
+pub static LOG_LEVEL_FILTER: LazyLock<LogLevelFilter> =
+    LazyLock::new(|| LogLevelFilter::new(LogLevel::Info));
+
+struct TemporaryLogLevel {
+    level: LogLevel,
+    process: Option<String>,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLevelFilterState {
+    pub level: LogLevel,
+    pub process_levels: HashMap<String, LogLevel>,
+    pub temporary_level: Option<LogLevel>,
+    pub temporary_level_process: Option<String>,
+    pub temporary_level_expires_in_sec: Option<u64>,
+}
+
+// Minimum level globally and per process (the first argument of LOGGER.write_*).
+// Temporary level is reverted automatically when its TTL expires
+pub struct LogLevelFilter {
+    level: RwLock<LogLevel>,
+    process_levels: RwLock<HashMap<String, LogLevel>>,
+    temporary_level: RwLock<Option<TemporaryLogLevel>>,
+}
+
+impl LogLevelFilter {
+    pub fn new(level: LogLevel) -> Self {
+        Self {
+            level: RwLock::new(level),
+            process_levels: RwLock::new(HashMap::new()),
+            temporary_level: RwLock::new(None),
+        }
+    }
+
+    // Values are in the LOG_LEVEL and PROCESS_LOG_LEVELS format, None keeps the current levels
+    pub fn configure(
+        &self,
+        level: Option<&str>,
+        process_levels: Option<&str>,
+    ) -> Result<(), String> {
+        if let Some(level) = level {
+            let Some(level) = LogLevel::parse(level) else {
+                return Err(format!("Invalid log level '{}'", level));
+            };
+
+            self.set_level(level);
+        }
+
+        if let Some(process_levels) = process_levels {
+            for (process, level) in parse_process_log_levels(process_levels)? {
+                self.set_process_level(process, level);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_level(&self, level: LogLevel) {
+        *self.level.write().unwrap() = level;
+    }
+
+    pub fn set_process_level(&self, process: impl Into<String>, level: LogLevel) {
+        self.process_levels
+            .write()
+            .unwrap()
+            .insert(process.into(), level);
+    }
+
+    // Process None means all processes. TTL is capped with MAX_TEMPORARY_LOG_LEVEL_TTL
+    pub fn set_temporary_level(&self, level: LogLevel, process: Option<String>, ttl: Duration) {
+        let ttl = ttl.min(MAX_TEMPORARY_LOG_LEVEL_TTL);
+
+        *self.temporary_level.write().unwrap() = Some(TemporaryLogLevel {
+            level,
+            process,
+            expires_at: Instant::now() + ttl,
+        });
+    }
+
+    pub fn reset_temporary_level(&self) {
+        *self.temporary_level.write().unwrap() = None;
+    }
+
+    pub fn get_min_level(&self, process: &str) -> LogLevel {
+        if let Some(temporary_level) = self.temporary_level.read().unwrap().as_ref() {
+            let is_matching = match &temporary_level.process {
+                Some(temporary_process) => temporary_process == process,
+                None => true,
+            };
+
+            if is_matching && temporary_level.expires_at > Instant::now() {
+                return temporary_level.level;
+            }
+        }
+
+        if let Some(level) = self.process_levels.read().unwrap().get(process) {
+            return *level;
+        }
+
+        *self.level.read().unwrap()
+    }
+
+    pub fn is_enabled(&self, level: LogLevel, process: &str) -> bool {
+        level >= self.get_min_level(process)
+    }
+
+    pub fn get_state(&self) -> LogLevelFilterState {
+        let now = Instant::now();
+        let temporary_level = self.temporary_level.read().unwrap();
+        let temporary_level = temporary_level.as_ref().filter(|itm| itm.expires_at > now);
+
+        LogLevelFilterState {
+            level: *self.level.read().unwrap(),
+            process_levels: self.process_levels.read().unwrap().clone(),
+            temporary_level: temporary_level.map(|itm| itm.level),
+            temporary_level_process: temporary_level.and_then(|itm| itm.process.clone()),
+            temporary_level_expires_in_sec: temporary_level
+                .map(|itm| (itm.expires_at - now).as_secs()),
+        }
+    }
+}
+
+// process1=debug;process2=warning
+pub fn parse_process_log_levels(value: &str) -> Result<Vec<(String, LogLevel)>, String> {
+    let mut result = Vec::new();
+
+    for item in value.split(';').filter(|itm| !itm.trim().is_empty()) {
+        let Some((process, level)) = item.split_once('=') else {
+            return Err(format!("Invalid process log level '{}'", item));
+        };
+
+        let Some(level) = LogLevel::parse(level) else {
+            return Err(format!(
+                "Invalid log level '{}' of process {}",
+                level, process
+            ));
+        };
+
+        result.push((process.trim().to_string(), level));
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_process_log_levels, LogLevelFilter, MAX_TEMPORARY_LOG_LEVEL_TTL};
+    use crate::LogLevel;
+
+    #[test]
+    fn test_process_level_wins_over_global() {
+        let filter = LogLevelFilter::new(LogLevel::Warning);
+        filter.set_process_level("Orders", LogLevel::Debug);
+
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Debug);
+        assert_eq!(filter.get_min_level("Payments"), LogLevel::Warning);
+        assert!(filter.is_enabled(LogLevel::Debug, "Orders"));
+        assert!(!filter.is_enabled(LogLevel::Info, "Payments"));
+    }
+
+    #[test]
+    fn test_temporary_level_wins_over_process_level() {
+        let filter = LogLevelFilter::new(LogLevel::Warning);
+        filter.set_process_level("Orders", LogLevel::Error);
+        filter.set_temporary_level(LogLevel::Debug, None, Duration::from_secs(60));
+
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Debug);
+        assert_eq!(filter.get_min_level("Payments"), LogLevel::Debug);
+
+        filter.reset_temporary_level();
+
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Error);
+    }
+
+    #[test]
+    fn test_temporary_level_of_process() {
+        let filter = LogLevelFilter::new(LogLevel::Warning);
+        filter.set_temporary_level(
+            LogLevel::Debug,
+            Some("Orders".to_string()),
+            Duration::from_secs(60),
+        );
+
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Debug);
+        assert_eq!(filter.get_min_level("Payments"), LogLevel::Warning);
+
+        let state = filter.get_state();
+        assert_eq!(state.temporary_level, Some(LogLevel::Debug));
+        assert_eq!(state.temporary_level_process.as_deref(), Some("Orders"));
+    }
+
+    #[test]
+    fn test_temporary_level_expires() {
+        let filter = LogLevelFilter::new(LogLevel::Warning);
+        filter.set_temporary_level(LogLevel::Debug, None, Duration::from_millis(20));
+
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Debug);
+
+        std::thread::sleep(Duration::from_millis(40));
+
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Warning);
+        assert!(filter.get_state().temporary_level.is_none());
+    }
+
+    #[test]
+    fn test_temporary_level_ttl_is_capped() {
+        let filter = LogLevelFilter::new(LogLevel::Warning);
+        filter.set_temporary_level(LogLevel::Debug, None, Duration::from_secs(24 * 60 * 60));
+
+        let expires_in_sec = filter.get_state().temporary_level_expires_in_sec.unwrap();
+        assert!(expires_in_sec <= MAX_TEMPORARY_LOG_LEVEL_TTL.as_secs());
+    }
+
+    #[test]
+    fn test_parse_process_log_levels() {
+        assert_eq!(
+            parse_process_log_levels(" Orders = debug;Payments=WARN;").unwrap(),
+            vec![
+                ("Orders".to_string(), LogLevel::Debug),
+                ("Payments".to_string(), LogLevel::Warning),
+            ]
+        );
+        assert!(parse_process_log_levels("").unwrap().is_empty());
+        assert!(parse_process_log_levels("Orders").is_err());
+        assert!(parse_process_log_levels("Orders=verbose").is_err());
+    }
+
+    #[test]
+    fn test_configure() {
+        let filter = LogLevelFilter::new(LogLevel::Info);
+
+        filter
+            .configure(Some("error"), Some("Orders=debug"))
+            .unwrap();
+
+        assert_eq!(filter.get_min_level("Payments"), LogLevel::Error);
+        assert_eq!(filter.get_min_level("Orders"), LogLevel::Debug);
+
+        assert!(filter.configure(Some("verbose"), None).is_err());
+        // Invalid value keeps the level
+        assert_eq!(filter.get_min_level("Payments"), LogLevel::Error);
+
+        filter.configure(None, None).unwrap();
+        assert_eq!(filter.get_min_level("Payments"), LogLevel::Error);
+    }
+}
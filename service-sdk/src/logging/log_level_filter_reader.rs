@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use my_logger::{MyLogEvent, MyLoggerReader};
+
+use crate::{LogLevel, LOG_LEVEL_FILTER};
+
+// SDK sinks are plugged into my_logger::LOGGER through it, so LOG_LEVEL_FILTER is the same for all of them
+pub struct LogLevelFilterReader {
+    inner: Arc<dyn MyLoggerReader + Send + Sync + 'static>,
+}
+
+impl LogLevelFilterReader {
+    pub fn new(inner: Arc<dyn MyLoggerReader + Send + Sync + 'static>) -> Self {
+        Self { inner }
+    }
+}
+
+impl MyLoggerReader for LogLevelFilterReader {
+    fn write_log(&self, log_event: Arc<MyLogEvent>) {
+        let level = LogLevel::from_my_logger(&log_event.level);
+
+        if LOG_LEVEL_FILTER.is_enabled(level, &log_event.process) {
+            self.inner.write_log(log_event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use my_logger::{MyLogEvent, MyLoggerReader};
+
+    use super::LogLevelFilterReader;
+    use crate::{LogLevel, LOG_LEVEL_FILTER};
+
+    struct RecordingReader {
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl MyLoggerReader for RecordingReader {
+        fn write_log(&self, log_event: Arc<MyLogEvent>) {
+            self.messages
+                .lock()
+                .unwrap()
+                .push(log_event.message.clone());
+        }
+    }
+
+    fn create_event(level: my_logger::LogLevel, message: &str) -> Arc<MyLogEvent> {
+        Arc::new(MyLogEvent {
+            dt: rust_extensions::date_time::DateTimeAsMicroseconds::now(),
+            level,
+            process: "LogLevelFilterReaderTest".to_string(),
+            message: message.to_string(),
+            context: None,
+        })
+    }
+
+    #[test]
+    fn test_events_below_min_level_are_dropped() {
+        LOG_LEVEL_FILTER.set_process_level("LogLevelFilterReaderTest", LogLevel::Warning);
+
+        let inner = Arc::new(RecordingReader {
+            messages: Mutex::new(Vec::new()),
+        });
+        let reader = LogLevelFilterReader::new(inner.clone());
+
+        reader.write_log(create_event(my_logger::LogLevel::Info, "info"));
+        reader.write_log(create_event(my_logger::LogLevel::Warning, "warning"));
+        reader.write_log(create_event(my_logger::LogLevel::Error, "error"));
+
+        assert_eq!(*inner.messages.lock().unwrap(), vec!["warning", "error"]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+
+use crate::{LogLevel, LOG_LEVEL_FILTER};
+
+pub const LOG_LEVEL_PATH: &str = "/api/log-level";
+const DEFAULT_TEMPORARY_LOG_LEVEL_TTL: Duration = Duration::from_secs(10 * 60);
+
+// GET    /api/log-level                                  - current levels
+// POST   /api/log-level?level=debug&process=X&ttl_sec=600 - temporary level, process is optional
+// DELETE /api/log-level                                  - revert temporary level now
+pub struct LogLevelMiddleware;
+
+impl LogLevelMiddleware {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for LogLevelMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        if ctx.request.http_path.as_str() != LOG_LEVEL_PATH {
+            return get_next.next(ctx).await;
+        }
+
+        match ctx.request.method.as_str() {
+            "GET" => {}
+            "POST" => {
+                let query = ctx.request.get_uri().query().unwrap_or_default();
+
+                let Some(level) =
+                    get_query_param(query, "level").and_then(|itm| LogLevel::parse(&itm))
+                else {
+                    return HttpOutput::as_text(
+                        "level query param is required: debug, info, warning, error, fatal"
+                            .to_string(),
+                    )
+                    .into_fail_result(400, false);
+                };
+
+                let ttl = match get_query_param(query, "ttl_sec") {
+                    Some(ttl_sec) => match ttl_sec.parse() {
+                        Ok(ttl_sec) => Duration::from_secs(ttl_sec),
+                        Err(_) => {
+                            return HttpOutput::as_text("Invalid ttl_sec".to_string())
+                                .into_fail_result(400, false);
+                        }
+                    },
+                    None => DEFAULT_TEMPORARY_LOG_LEVEL_TTL,
+                };
+
+                let process = get_query_param(query, "process");
+
+                my_logger::LOGGER.write_warning(
+                    "LogLevel".to_string(),
+                    format!(
+                        "Temporary log level {} for {} during {} sec",
+                        level.as_str(),
+                        process.as_deref().unwrap_or("all processes"),
+                        ttl.as_secs()
+                    ),
+                    my_logger::LogEventCtx::new(),
+                );
+
+                LOG_LEVEL_FILTER.set_temporary_level(level, process, ttl);
+            }
+            "DELETE" => {
+                LOG_LEVEL_FILTER.reset_temporary_level();
+            }
+            _ => {
+                return HttpOutput::as_text("Method is not allowed".to_string())
+                    .into_fail_result(405, false);
+            }
+        }
+
+        HttpOutput::as_json(LOG_LEVEL_FILTER.get_state()).into_ok_result(false)
+    }
+}
+
+fn get_query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| decode_query_value(value))
+}
+
+fn decode_query_value(value: &str) -> String {
+    let value = value.replace('+', " ");
+    percent_encoding::percent_decode_str(&value)
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_query_param;
+
+    #[test]
+    fn test_query_param_is_decoded() {
+        let query = "level=debug&process=Payments%20Processor&ttl_sec=600";
+
+        assert_eq!(get_query_param(query, "level").as_deref(), Some("debug"));
+        assert_eq!(
+            get_query_param(query, "process").as_deref(),
+            Some("Payments Processor")
+        );
+        assert_eq!(
+            get_query_param("process=Orders+Api", "process").as_deref(),
+            Some("Orders Api")
+        );
+        assert!(get_query_param(query, "unknown").is_none());
+    }
+}
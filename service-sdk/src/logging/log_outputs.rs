@@ -0,0 +1,120 @@
+pub const LOG_OUTPUT_ENV: &str = "LOG_OUTPUT";
+
+// Sinks which receive the events written with my_logger::LOGGER
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogOutputs {
+    // Can be set only when the crate is built with seq feature
+    pub seq: bool,
+    // One JSON object per line on stdout, for the clusters which ship logs from stdout
+    pub json_console: bool,
+}
+
+impl LogOutputs {
+    // Seq only if output is not set, as it was before the console sink appeared
+    pub fn get_default() -> Self {
+        Self {
+            seq: cfg!(feature = "seq"),
+            json_console: false,
+        }
+    }
+
+    // seq,json
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut result = Self {
+            seq: false,
+            json_console: false,
+        };
+
+        for item in value.split(',') {
+            match item.trim().to_lowercase().as_str() {
+                "seq" if cfg!(feature = "seq") => result.seq = true,
+                "seq" => {
+                    return Err("Seq log output requires seq feature of service-sdk".to_string())
+                }
+                "json" | "json-console" => result.json_console = true,
+                "" | "none" => {}
+                _ => {
+                    return Err(format!(
+                        "Invalid log output '{}'. Expected: seq, json, none",
+                        item.trim()
+                    ))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    // Value of LOG_OUTPUT env variable or the settings, None means default outputs
+    pub fn from_value(value: Option<&str>) -> Result<Self, String> {
+        match value {
+            Some(value) => Self::parse(value),
+            None => Ok(Self::get_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogOutputs;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            LogOutputs::parse(" JSON").unwrap(),
+            LogOutputs {
+                seq: false,
+                json_console: true,
+            }
+        );
+        assert_eq!(
+            LogOutputs::parse("json-console").unwrap(),
+            LogOutputs {
+                seq: false,
+                json_console: true,
+            }
+        );
+        assert_eq!(
+            LogOutputs::parse("none").unwrap(),
+            LogOutputs {
+                seq: false,
+                json_console: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_value() {
+        assert!(LogOutputs::parse("json,loki").is_err());
+        assert!(LogOutputs::from_value(Some("stdout")).is_err());
+    }
+
+    #[cfg(feature = "seq")]
+    #[test]
+    fn test_parse_seq() {
+        assert_eq!(
+            LogOutputs::parse("seq,json").unwrap(),
+            LogOutputs {
+                seq: true,
+                json_console: true,
+            }
+        );
+    }
+
+    #[cfg(not(feature = "seq"))]
+    #[test]
+    fn test_seq_is_rejected_without_feature() {
+        assert!(LogOutputs::parse("seq").is_err());
+        assert!(LogOutputs::parse("json,seq").is_err());
+        assert!(!LogOutputs::get_default().seq);
+    }
+
+    #[test]
+    fn test_default_when_not_set() {
+        assert_eq!(
+            LogOutputs::from_value(None).unwrap(),
+            LogOutputs::get_default()
+        );
+        assert!(!LogOutputs::get_default().json_console);
+    }
+}
@@ -0,0 +1,8 @@
+// Env variable wins over the settings, so the value can be changed for one deployment.
+// Empty values are treated as not set
+pub fn get_log_setting(env_name: &str, settings_value: Option<String>) -> Option<String> {
+    std::env::var(env_name)
+        .ok()
+        .filter(|itm| !itm.trim().is_empty())
+        .or(settings_value.filter(|itm| !itm.trim().is_empty()))
+}
@@ -0,0 +1,15 @@
+mod json_console_logger;
+mod log_level;
+mod log_level_filter;
+mod log_level_filter_reader;
+mod log_level_middleware;
+mod log_outputs;
+mod log_settings;
+
+pub use json_console_logger::*;
+pub use log_level::*;
+pub use log_level_filter::*;
+pub use log_level_filter_reader::*;
+pub use log_level_middleware::*;
+pub use log_outputs::*;
+pub use log_settings::*;
@@ -0,0 +1,7 @@
+mod otel_tracing;
+mod server_spans;
+mod trace_headers;
+
+pub use otel_tracing::*;
+pub use server_spans::*;
+pub use trace_headers::*;
@@ -0,0 +1,80 @@
+use opentelemetry::{global, trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::TracerProvider, Resource};
+
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+pub const SDK_TRACER_NAME: &str = "service-sdk";
+
+// Endpoint from the environment wins over the one set in code. Without both tracing is not started
+// and all the SDK spans are no-op
+pub fn get_otlp_endpoint(configured: Option<&str>) -> Option<String> {
+    if let Ok(endpoint) = std::env::var(OTLP_ENDPOINT_ENV) {
+        if !endpoint.is_empty() {
+            return Some(endpoint);
+        }
+    }
+
+    configured.map(|itm| itm.to_string())
+}
+
+// Exports spans over OTLP/gRPC in batches. Has to be called inside tokio runtime
+pub fn start_otel_tracing(
+    app_name: &str,
+    app_version: &str,
+    otlp_endpoint: &str,
+) -> Result<TracerProvider, String> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(otlp_endpoint)
+        .build()
+        .map_err(|err| {
+            format!(
+                "Can not create OTLP exporter for {}. {}",
+                otlp_endpoint, err
+            )
+        })?;
+
+    let tracer_provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", app_name.to_string()),
+            KeyValue::new("service.version", app_version.to_string()),
+        ]))
+        .build();
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(tracer_provider.clone());
+
+    // Warm up, so the first request does not pay for tracer creation
+    let _ = tracer_provider.tracer(SDK_TRACER_NAME);
+
+    Ok(tracer_provider)
+}
+
+pub fn get_sdk_tracer() -> global::BoxedTracer {
+    global::tracer(SDK_TRACER_NAME)
+}
+
+// Collector stand-in for tests. Spans are exported synchronously when they end
+#[cfg(test)]
+pub(crate) fn start_test_tracing() -> opentelemetry_sdk::testing::trace::InMemorySpanExporter {
+    use std::sync::OnceLock;
+
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+
+    static EXPORTER: OnceLock<InMemorySpanExporter> = OnceLock::new();
+
+    EXPORTER
+        .get_or_init(|| {
+            let exporter = InMemorySpanExporter::default();
+            let tracer_provider = TracerProvider::builder()
+                .with_simple_exporter(exporter.clone())
+                .build();
+
+            global::set_text_map_propagator(TraceContextPropagator::new());
+            global::set_tracer_provider(tracer_provider);
+
+            exporter
+        })
+        .clone()
+}
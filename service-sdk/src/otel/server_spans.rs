@@ -0,0 +1,97 @@
+use opentelemetry::{
+    trace::{SpanKind, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+
+use crate::get_sdk_tracer;
+
+pub fn start_server_span(name: String, parent: &Context, attributes: Vec<KeyValue>) -> Context {
+    start_span(name, SpanKind::Server, parent, attributes)
+}
+
+fn start_span(
+    name: String,
+    kind: SpanKind,
+    parent: &Context,
+    attributes: Vec<KeyValue>,
+) -> Context {
+    let tracer = get_sdk_tracer();
+    let span = tracer
+        .span_builder(name)
+        .with_kind(kind)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, parent);
+
+    parent.with_span(span)
+}
+
+// Span is renamed to the route template when it is known, e.g. GET /api/orders/{id}.
+// Status is Error for 5xx responses and Ok for the rest
+pub fn end_http_server_span(cx: &Context, method: &str, route: &str, status_code: u16) {
+    let span = cx.span();
+    span.update_name(format!("{} {}", method, route));
+    span.set_attribute(KeyValue::new("http.route", route.to_string()));
+    span.set_attribute(KeyValue::new(
+        "http.response.status_code",
+        status_code as i64,
+    ));
+
+    if status_code >= 500 {
+        span.set_status(Status::error(format!("HTTP {}", status_code)));
+    } else {
+        span.set_status(Status::Ok);
+    }
+
+    span.end();
+}
+
+pub fn start_grpc_server_span(path: &str, parent: &Context) -> Context {
+    let mut attributes = vec![KeyValue::new("rpc.system", "grpc")];
+
+    // /package.Service/Method
+    if let Some((service, method)) = path.trim_start_matches('/').split_once('/') {
+        attributes.push(KeyValue::new("rpc.service", service.to_string()));
+        attributes.push(KeyValue::new("rpc.method", method.to_string()));
+    }
+
+    start_server_span(path.trim_start_matches('/').to_string(), parent, attributes)
+}
+
+// Server side errors only, client errors like NotFound leave the span Unset
+pub fn end_grpc_server_span(cx: &Context, grpc_code: i32, is_server_error: bool) {
+    let span = cx.span();
+    span.set_attribute(KeyValue::new("rpc.grpc.status_code", grpc_code as i64));
+
+    if is_server_error {
+        span.set_status(Status::error(format!("gRPC status {}", grpc_code)));
+    }
+
+    span.end();
+}
+
+// Parent is the context of the publisher taken from the message headers
+pub fn start_sb_consumer_span(topic_id: &str, queue_id: &str, parent: &Context) -> Context {
+    start_span(
+        format!("{} process", topic_id),
+        SpanKind::Consumer,
+        parent,
+        vec![
+            KeyValue::new("messaging.system", "servicebus"),
+            KeyValue::new("messaging.operation.type", "process"),
+            KeyValue::new("messaging.destination.name", topic_id.to_string()),
+            KeyValue::new("messaging.consumer.group.name", queue_id.to_string()),
+        ],
+    )
+}
+
+pub fn end_sb_consumer_span(cx: &Context, is_error: bool) {
+    let span = cx.span();
+
+    if is_error {
+        span.set_status(Status::error("Messages are not handled"));
+    } else {
+        span.set_status(Status::Ok);
+    }
+
+    span.end();
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use opentelemetry::{
+    global,
+    propagation::{Extractor, Injector},
+    Context,
+};
+
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+// W3C trace context headers. Header names are lower case
+#[derive(Debug, Clone, Default)]
+pub struct TraceHeaders {
+    headers: HashMap<String, String>,
+}
+
+impl TraceHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Takes only the headers which are needed for propagation
+    pub fn from_headers<'s>(headers: impl IntoIterator<Item = (&'s str, &'s str)>) -> Self {
+        let mut result = Self::new();
+
+        for (name, value) in headers {
+            let name = name.to_lowercase();
+            if name == TRACEPARENT_HEADER || name == TRACESTATE_HEADER {
+                result.headers.insert(name, value.to_string());
+            }
+        }
+
+        result
+    }
+
+    pub fn get_context(&self) -> Context {
+        global::get_text_map_propagator(|propagator| propagator.extract(self))
+    }
+
+    pub fn from_context(cx: &Context) -> Self {
+        let mut result = Self::new();
+        global::get_text_map_propagator(|propagator| propagator.inject_context(cx, &mut result));
+        result
+    }
+
+    pub fn into_vec(self) -> Vec<(String, String)> {
+        self.headers.into_iter().collect()
+    }
+}
+
+impl Extractor for TraceHeaders {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.headers
+            .get(&key.to_lowercase())
+            .map(|itm| itm.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.headers.keys().map(|itm| itm.as_str()).collect()
+    }
+}
+
+impl Injector for TraceHeaders {
+    fn set(&mut self, key: &str, value: String) {
+        self.headers.insert(key.to_lowercase(), value);
+    }
+}
+
+// Headers to add to outgoing calls and Service Bus messages, so the receiver continues current trace
+pub fn inject_trace_headers() -> Vec<(String, String)> {
+    TraceHeaders::from_context(&Context::current()).into_vec()
+}
+
+// Context of the sender from incoming headers, e.g. headers of Service Bus message
+pub fn extract_trace_context<'s>(headers: impl IntoIterator<Item = (&'s str, &'s str)>) -> Context {
+    TraceHeaders::from_headers(headers).get_context()
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::{
+        trace::{TraceContextExt, Tracer},
+        Context,
+    };
+
+    use super::{TraceHeaders, TRACEPARENT_HEADER};
+
+    #[test]
+    fn test_trace_context_goes_through_headers() {
+        crate::start_test_tracing();
+
+        let span = crate::get_sdk_tracer().start("publish");
+        let cx = Context::current_with_span(span);
+        let trace_id = cx.span().span_context().trace_id();
+
+        let headers = {
+            let _guard = cx.clone().attach();
+            super::inject_trace_headers()
+        };
+
+        let traceparent = headers
+            .iter()
+            .find(|(name, _)| name == TRACEPARENT_HEADER)
+            .map(|(_, value)| value.clone())
+            .unwrap();
+        assert!(traceparent.contains(&trace_id.to_string()));
+
+        let extracted = super::extract_trace_context(
+            headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+
+        assert!(extracted.span().span_context().is_remote());
+        assert_eq!(extracted.span().span_context().trace_id(), trace_id);
+    }
+
+    #[test]
+    fn test_only_trace_headers_are_taken() {
+        let trace_headers = TraceHeaders::from_headers([
+            (
+                "TraceParent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            ),
+            ("authorization", "secret"),
+        ]);
+
+        assert_eq!(
+            trace_headers.into_vec(),
+            vec![(
+                TRACEPARENT_HEADER.to_string(),
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_string()
+            )]
+        );
+    }
+}
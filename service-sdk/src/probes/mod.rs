@@ -0,0 +1,13 @@
+mod readiness_check;
+mod probes_middleware;
+#[cfg(feature = "my-nosql-data-reader-sdk")]
+mod no_sql_readiness_check;
+#[cfg(feature = "my-service-bus")]
+mod sb_readiness_check;
+
+pub use readiness_check::*;
+pub use probes_middleware::*;
+#[cfg(feature = "my-nosql-data-reader-sdk")]
+pub use no_sql_readiness_check::*;
+#[cfg(feature = "my-service-bus")]
+pub use sb_readiness_check::*;
@@ -0,0 +1,35 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ReadinessCheck;
+
+// Every reader taken from ServiceContext::get_ns_reader has to receive its initial snapshot
+pub struct NoSqlReadinessCheck {
+    pending_readers: AtomicUsize,
+}
+
+impl NoSqlReadinessCheck {
+    pub fn new() -> Self {
+        Self {
+            pending_readers: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn reader_registered(&self) {
+        self.pending_readers.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn reader_received_snapshot(&self) {
+        self.pending_readers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait::async_trait]
+impl ReadinessCheck for NoSqlReadinessCheck {
+    fn get_name(&self) -> &str {
+        "my-no-sql-reader"
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.pending_readers.load(Ordering::SeqCst) == 0
+    }
+}
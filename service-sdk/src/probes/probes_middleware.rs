@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+
+use crate::ReadinessProbe;
+
+pub const STARTUP_PROBE_PATH: &str = "/api/startup";
+pub const READINESS_PROBE_PATH: &str = "/api/ready";
+
+pub struct ProbesMiddleware {
+    readiness_probe: Arc<ReadinessProbe>,
+}
+
+impl ProbesMiddleware {
+    pub fn new(readiness_probe: Arc<ReadinessProbe>) -> Self {
+        Self { readiness_probe }
+    }
+
+    // Status code and text of the probe, None if the path is not a probe
+    async fn get_probe_response(&self, path: &str) -> Option<(u16, String)> {
+        if path == STARTUP_PROBE_PATH {
+            if self.readiness_probe.is_started() {
+                return Some((200, "Started".to_string()));
+            }
+
+            return Some((503, "Not started".to_string()));
+        }
+
+        if path == READINESS_PROBE_PATH {
+            let not_ready = self.readiness_probe.get_not_ready().await;
+
+            if not_ready.is_empty() {
+                return Some((200, "Ready".to_string()));
+            }
+
+            return Some((503, format!("Not ready: {}", not_ready.join(", "))));
+        }
+
+        None
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for ProbesMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        let path = ctx.request.http_path.as_str();
+
+        if let Some((status_code, text)) = self.get_probe_response(path).await {
+            if status_code == 200 {
+                return HttpOutput::as_text(text).into_ok_result(false);
+            }
+
+            return HttpOutput::as_text(text).into_fail_result(status_code, false);
+        }
+
+        get_next.next(ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rust_extensions::AppStates;
+
+    use super::{ProbesMiddleware, READINESS_PROBE_PATH, STARTUP_PROBE_PATH};
+    use crate::ReadinessProbe;
+
+    fn create_middleware() -> (Arc<AppStates>, ProbesMiddleware) {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        let readiness_probe = Arc::new(ReadinessProbe::new(app_states.clone()));
+        (app_states, ProbesMiddleware::new(readiness_probe))
+    }
+
+    #[tokio::test]
+    async fn startup_probe_follows_initialization() {
+        let (app_states, middleware) = create_middleware();
+
+        assert_eq!(
+            middleware.get_probe_response(STARTUP_PROBE_PATH).await,
+            Some((503, "Not started".to_string()))
+        );
+
+        app_states.set_initialized();
+
+        assert_eq!(
+            middleware.get_probe_response(STARTUP_PROBE_PATH).await,
+            Some((200, "Started".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn readiness_probe_lists_failed_checks() {
+        let (app_states, middleware) = create_middleware();
+
+        assert_eq!(
+            middleware.get_probe_response(READINESS_PROBE_PATH).await,
+            Some((503, "Not ready: initialization".to_string()))
+        );
+
+        app_states.set_initialized();
+
+        assert_eq!(
+            middleware.get_probe_response(READINESS_PROBE_PATH).await,
+            Some((200, "Ready".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn other_paths_are_passed_through() {
+        let (_, middleware) = create_middleware();
+
+        assert_eq!(middleware.get_probe_response("/api/isalive").await, None);
+        assert_eq!(middleware.get_probe_response("/api/ready/x").await, None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use rust_extensions::AppStates;
+use tokio::sync::Notify;
+
+#[async_trait::async_trait]
+pub trait ReadinessCheck {
+    fn get_name(&self) -> &str;
+    async fn is_ready(&self) -> bool;
+}
+
+pub struct ReadinessProbe {
+    app_states: Arc<AppStates>,
+    checks: Mutex<Vec<Arc<dyn ReadinessCheck + Send + Sync + 'static>>>,
+    draining: AtomicBool,
+    draining_started: Notify,
+}
+
+impl ReadinessProbe {
+    pub fn new(app_states: Arc<AppStates>) -> Self {
+        Self {
+            app_states,
+            checks: Mutex::new(vec![]),
+            draining: AtomicBool::new(false),
+            draining_started: Notify::new(),
+        }
+    }
+
+    pub fn register_check(&self, check: Arc<dyn ReadinessCheck + Send + Sync + 'static>) {
+        self.checks.lock().unwrap().push(check);
+    }
+
+    // Shutdown is requested, but requests are still served until the pre-drain delay is over
+    pub fn set_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+        self.draining_started.notify_waiters();
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    // Lets status publishers react to draining without polling
+    pub async fn wait_until_draining(&self) {
+        let notified = self.draining_started.notified();
+
+        if self.is_draining() {
+            return;
+        }
+
+        notified.await;
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.app_states.is_initialized()
+    }
+
+    // Returns names of the checks which are not passed yet
+    pub async fn get_not_ready(&self) -> Vec<String> {
+        let mut result = vec![];
+
+        if !self.app_states.is_initialized() {
+            result.push("initialization".to_string());
+        }
+
+        if self.is_draining() || self.app_states.is_shutting_down() {
+            result.push("shutdown".to_string());
+        }
+
+        let checks = self.checks.lock().unwrap().clone();
+
+        for check in checks {
+            if !check.is_ready().await {
+                result.push(check.get_name().to_string());
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use rust_extensions::AppStates;
+
+    use super::{ReadinessCheck, ReadinessProbe};
+
+    struct TestCheck {
+        name: &'static str,
+        ready: AtomicBool,
+    }
+
+    impl TestCheck {
+        fn new(name: &'static str, ready: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                ready: AtomicBool::new(ready),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ReadinessCheck for TestCheck {
+        fn get_name(&self) -> &str {
+            self.name
+        }
+
+        async fn is_ready(&self) -> bool {
+            self.ready.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn not_ready_until_initialized() {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        let probe = ReadinessProbe::new(app_states.clone());
+
+        assert!(!probe.is_started());
+        assert_eq!(probe.get_not_ready().await, vec!["initialization"]);
+
+        app_states.set_initialized();
+
+        assert!(probe.is_started());
+        assert!(probe.get_not_ready().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_checks_are_listed_in_registration_order() {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        app_states.set_initialized();
+        let probe = ReadinessProbe::new(app_states);
+
+        let no_sql = TestCheck::new("no-sql", false);
+        probe.register_check(TestCheck::new("service-bus", true));
+        probe.register_check(no_sql.clone());
+        probe.register_check(TestCheck::new("cache", false));
+
+        assert_eq!(probe.get_not_ready().await, vec!["no-sql", "cache"]);
+
+        no_sql.ready.store(true, Ordering::SeqCst);
+
+        assert_eq!(probe.get_not_ready().await, vec!["cache"]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_makes_probe_not_ready() {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        app_states.set_initialized();
+        let probe = ReadinessProbe::new(app_states.clone());
+
+        app_states.set_shutting_down();
+
+        assert_eq!(probe.get_not_ready().await, vec!["shutdown"]);
+    }
+
+    #[tokio::test]
+    async fn draining_wakes_waiters() {
+        let app_states = Arc::new(AppStates::create_un_initialized());
+        app_states.set_initialized();
+        let probe = Arc::new(ReadinessProbe::new(app_states));
+
+        let waiter = tokio::spawn({
+            let probe = probe.clone();
+            async move { probe.wait_until_draining().await }
+        });
+
+        tokio::task::yield_now().await;
+        probe.set_draining();
+
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Already draining, so it returns at once
+        probe.wait_until_draining().await;
+        assert_eq!(probe.get_not_ready().await, vec!["shutdown"]);
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use my_service_bus::client::MyServiceBusClient;
+
+use crate::ReadinessCheck;
+
+pub struct SbReadinessCheck {
+    sb_client: Arc<MyServiceBusClient>,
+}
+
+impl SbReadinessCheck {
+    pub fn new(sb_client: Arc<MyServiceBusClient>) -> Self {
+        Self { sb_client }
+    }
+}
+
+#[async_trait::async_trait]
+impl ReadinessCheck for SbReadinessCheck {
+    fn get_name(&self) -> &str {
+        "my-service-bus"
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.sb_client.is_connected().await
+    }
+}
@@ -0,0 +1,67 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use my_grpc_extensions::hyper;
+use my_grpc_extensions::tonic::codegen::http::HeaderValue;
+use tower::{Layer, Service};
+
+use crate::REQUEST_ID_HEADER;
+
+#[derive(Debug, Clone, Default)]
+pub struct GrpcRequestIdLayer;
+
+impl<S> Layer<S> for GrpcRequestIdLayer {
+    type Service = GrpcRequestIdMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        GrpcRequestIdMiddleware { inner: service }
+    }
+}
+
+// x-request-id metadata is read or generated, available to handlers with get_request_id
+// and returned in response metadata
+#[derive(Debug, Clone)]
+pub struct GrpcRequestIdMiddleware<S> {
+    inner: S,
+}
+
+type BoxFuture<'a, T> = Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+impl<S, ReqBody, ResBody> Service<hyper::Request<ReqBody>> for GrpcRequestIdMiddleware<S>
+where
+    S: Service<hyper::Request<ReqBody>, Response = hyper::Response<ResBody>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: hyper::Request<ReqBody>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let incoming = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok());
+        let request_id = crate::get_or_generate_request_id(incoming);
+
+        Box::pin(async move {
+            let mut response = crate::with_request_id(request_id.clone(), inner.call(req)).await?;
+
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                response.headers_mut().insert(REQUEST_ID_HEADER, value);
+            }
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,11 @@
+mod request_id;
+mod request_id_log_reader;
+mod request_id_middleware;
+#[cfg(feature = "grpc")]
+mod grpc_request_id_layer;
+
+pub use request_id::*;
+pub use request_id_log_reader::*;
+pub use request_id_middleware::*;
+#[cfg(feature = "grpc")]
+pub use grpc_request_id_layer::*;
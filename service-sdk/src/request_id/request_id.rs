@@ -0,0 +1,81 @@
+use std::future::Future;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const REQUEST_ID_LOG_KEY: &str = "RequestId";
+
+// Longer or non printable ids from the callers are replaced with a new one
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// Id of the request currently being handled by this task
+pub fn get_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
+pub async fn with_request_id<TFuture: Future>(
+    request_id: String,
+    future: TFuture,
+) -> TFuture::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+// Incoming id if it is valid, otherwise a new one
+pub fn get_or_generate_request_id(incoming: Option<&str>) -> String {
+    if let Some(incoming) = incoming {
+        let is_valid = !incoming.is_empty()
+            && incoming.len() <= MAX_REQUEST_ID_LEN
+            && incoming.chars().all(|c| c.is_ascii_graphic());
+
+        if is_valid {
+            return incoming.to_string();
+        }
+    }
+
+    uuid::Uuid::new_v4().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_or_generate_request_id;
+
+    fn is_generated(request_id: &str) -> bool {
+        uuid::Uuid::parse_str(request_id).is_ok()
+    }
+
+    #[test]
+    fn test_incoming_request_id_is_kept() {
+        assert_eq!(get_or_generate_request_id(Some("abc-123")), "abc-123");
+    }
+
+    #[test]
+    fn test_missing_request_id_is_generated() {
+        let first = get_or_generate_request_id(None);
+        let second = get_or_generate_request_id(None);
+
+        assert!(is_generated(&first));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_empty_request_id_is_replaced() {
+        assert!(is_generated(&get_or_generate_request_id(Some(""))));
+    }
+
+    #[test]
+    fn test_oversized_request_id_is_replaced() {
+        let max_len = "a".repeat(super::MAX_REQUEST_ID_LEN);
+        assert_eq!(get_or_generate_request_id(Some(&max_len)), max_len);
+
+        let oversized = "a".repeat(super::MAX_REQUEST_ID_LEN + 1);
+        assert!(is_generated(&get_or_generate_request_id(Some(&oversized))));
+    }
+
+    #[test]
+    fn test_non_printable_request_id_is_replaced() {
+        assert!(is_generated(&get_or_generate_request_id(Some("abc 123"))));
+        assert!(is_generated(&get_or_generate_request_id(Some("abc\n123"))));
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use my_logger::{MyLogEvent, MyLoggerReader};
+
+use crate::REQUEST_ID_LOG_KEY;
+
+// Adds RequestId of the request handled by the current task to every event written to the sink.
+// my_logger calls readers from the task which writes the event, so the task local is still there
+pub struct RequestIdLogReader {
+    inner: Arc<dyn MyLoggerReader + Send + Sync + 'static>,
+}
+
+impl RequestIdLogReader {
+    pub fn new(inner: Arc<dyn MyLoggerReader + Send + Sync + 'static>) -> Self {
+        Self { inner }
+    }
+}
+
+impl MyLoggerReader for RequestIdLogReader {
+    fn write_log(&self, log_event: Arc<MyLogEvent>) {
+        let Some(request_id) = crate::get_request_id() else {
+            self.inner.write_log(log_event);
+            return;
+        };
+
+        let mut context = log_event.context.clone().unwrap_or_default();
+
+        // Id set by the caller wins
+        context
+            .entry(REQUEST_ID_LOG_KEY.to_string())
+            .or_insert(request_id);
+
+        self.inner.write_log(Arc::new(MyLogEvent {
+            dt: log_event.dt,
+            level: log_event.level,
+            process: log_event.process.clone(),
+            message: log_event.message.clone(),
+            context: Some(context),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use my_logger::{MyLogEvent, MyLoggerReader};
+
+    use super::RequestIdLogReader;
+    use crate::REQUEST_ID_LOG_KEY;
+
+    struct RecordingReader {
+        contexts: Mutex<Vec<Option<HashMap<String, String>>>>,
+    }
+
+    impl MyLoggerReader for RecordingReader {
+        fn write_log(&self, log_event: Arc<MyLogEvent>) {
+            self.contexts
+                .lock()
+                .unwrap()
+                .push(log_event.context.clone());
+        }
+    }
+
+    fn create_event(context: Option<HashMap<String, String>>) -> Arc<MyLogEvent> {
+        Arc::new(MyLogEvent {
+            dt: rust_extensions::date_time::DateTimeAsMicroseconds::now(),
+            level: my_logger::LogLevel::Info,
+            process: "RequestIdLogReaderTest".to_string(),
+            message: "message".to_string(),
+            context,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_added_to_events_of_the_request() {
+        let inner = Arc::new(RecordingReader {
+            contexts: Mutex::new(Vec::new()),
+        });
+        let reader = RequestIdLogReader::new(inner.clone());
+
+        reader.write_log(create_event(None));
+
+        crate::with_request_id("abc-123".to_string(), async {
+            reader.write_log(create_event(None));
+
+            let mut context = HashMap::new();
+            context.insert("OrderId".to_string(), "5".to_string());
+            reader.write_log(create_event(Some(context)));
+        })
+        .await;
+
+        let contexts = inner.contexts.lock().unwrap();
+
+        assert!(contexts[0].is_none());
+        assert_eq!(
+            contexts[1].as_ref().unwrap().get(REQUEST_ID_LOG_KEY),
+            Some(&"abc-123".to_string())
+        );
+
+        let context = contexts[2].as_ref().unwrap();
+        assert_eq!(
+            context.get(REQUEST_ID_LOG_KEY),
+            Some(&"abc-123".to_string())
+        );
+        assert_eq!(context.get("OrderId"), Some(&"5".to_string()));
+    }
+}
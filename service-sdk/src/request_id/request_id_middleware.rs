@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use hyper::header::{HeaderValue, CONTENT_TYPE, LOCATION};
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+use my_logger::LogEventCtx;
+
+use crate::REQUEST_ID_HEADER;
+
+// Takes X-Request-Id from the request or generates a new one and returns it in the response.
+// Has to be the first middleware: fail results are returned as responses, so the ones
+// before it see every request as succeeded
+pub struct RequestIdMiddleware;
+
+impl RequestIdMiddleware {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for RequestIdMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        let incoming = ctx
+            .request
+            .get_headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok());
+
+        let request_id = crate::get_or_generate_request_id(incoming);
+
+        // Fail result is logged inside the scope as well, so it gets the request id
+        let ok_result = crate::with_request_id(request_id.clone(), async {
+            let result = get_next.next(ctx).await;
+            add_request_id_header(ctx, result, request_id)
+        })
+        .await;
+
+        Ok(ok_result)
+    }
+}
+
+// my-http-server writes fail results, empty outputs and redirects without custom headers,
+// so they are turned into raw responses with the same status and content
+fn add_request_id_header(
+    ctx: &HttpContext,
+    result: Result<HttpOkResult, HttpFailResult>,
+    request_id: String,
+) -> HttpOkResult {
+    let status_code = crate::sdk_metrics::get_status_code(&result);
+
+    let mut ok_result = match result {
+        Ok(ok_result) => ok_result,
+        Err(fail_result) => {
+            // my-http-server does not log it anymore, since it gets a response
+            if fail_result.write_to_log {
+                my_logger::LOGGER.write_warning(
+                    "HttpRequest".to_string(),
+                    format!(
+                        "{} {} {}",
+                        ctx.request.method,
+                        ctx.request.http_path.as_str(),
+                        status_code
+                    ),
+                    LogEventCtx::new(),
+                );
+            }
+
+            let response = hyper::Response::builder()
+                .status(status_code)
+                .header(CONTENT_TYPE, fail_result.content_type.to_string())
+                .body(fail_result.content.into())
+                .unwrap();
+
+            HttpOkResult {
+                write_telemetry: fail_result.write_telemetry,
+                output: HttpOutput::Raw(response),
+            }
+        }
+    };
+
+    match &mut ok_result.output {
+        HttpOutput::Content { headers, .. } => {
+            headers
+                .get_or_insert_with(Default::default)
+                .insert(REQUEST_ID_HEADER.to_string(), request_id);
+            return ok_result;
+        }
+        HttpOutput::Empty => {
+            ok_result.output = HttpOutput::Raw(
+                hyper::Response::builder()
+                    .status(status_code)
+                    .body(Vec::new().into())
+                    .unwrap(),
+            );
+        }
+        HttpOutput::Redirect { url, .. } => {
+            let url = url.clone();
+            ok_result.output = HttpOutput::Raw(
+                hyper::Response::builder()
+                    .status(status_code)
+                    .header(LOCATION, url)
+                    .body(Vec::new().into())
+                    .unwrap(),
+            );
+        }
+        _ => {}
+    }
+
+    if let HttpOutput::Raw(response) = &mut ok_result.output {
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+    }
+
+    ok_result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use my_http_server::{
+        HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpRequest, HttpServerMiddleware,
+        HttpServerRequestFlow,
+    };
+
+    use super::RequestIdMiddleware;
+    use crate::REQUEST_ID_HEADER;
+
+    // Output kind depends on the path
+    struct OutputHandler;
+
+    #[async_trait]
+    impl HttpServerMiddleware for OutputHandler {
+        async fn handle_request(
+            &self,
+            ctx: &mut HttpContext,
+            _get_next: &mut HttpServerRequestFlow,
+        ) -> Result<HttpOkResult, HttpFailResult> {
+            match ctx.request.http_path.as_str() {
+                "/content" => {
+                    HttpOutput::as_text(crate::get_request_id().unwrap()).into_ok_result(false)
+                }
+                "/empty" => HttpOutput::Empty.into_ok_result(false),
+                "/redirect" => HttpOutput::Redirect {
+                    url: "/content".to_string(),
+                    permanent: false,
+                }
+                .into_ok_result(false),
+                _ => HttpOutput::as_text("Not valid".to_string()).into_fail_result(400, false),
+            }
+        }
+    }
+
+    async fn handle(path: &str) -> HttpOkResult {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, "abc-123".parse().unwrap());
+
+        let mut ctx = HttpContext::new(HttpRequest::new("GET", path, headers));
+        let mut flow = HttpServerRequestFlow::new(vec![
+            Arc::new(RequestIdMiddleware::new()),
+            Arc::new(OutputHandler),
+        ]);
+
+        flow.next(&mut ctx).await.unwrap()
+    }
+
+    fn get_raw_response(ok_result: HttpOkResult) -> hyper::Response<Vec<u8>> {
+        match ok_result.output {
+            HttpOutput::Raw(response) => response,
+            _ => panic!("Raw output is expected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_content_gets_request_id_header() {
+        let ok_result = handle("/content").await;
+
+        let HttpOutput::Content {
+            headers, content, ..
+        } = ok_result.output
+        else {
+            panic!("Content is expected");
+        };
+
+        assert_eq!(content, b"abc-123");
+        assert_eq!(
+            headers
+                .unwrap()
+                .get(REQUEST_ID_HEADER)
+                .map(|itm| itm.as_str()),
+            Some("abc-123")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fail_result_keeps_status_and_content() {
+        let response = get_raw_response(handle("/fail").await);
+
+        assert_eq!(response.status().as_u16(), 400);
+        assert_eq!(
+            response.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "abc-123"
+        );
+        assert_eq!(response.body().as_slice(), b"Not valid");
+    }
+
+    #[tokio::test]
+    async fn test_empty_and_redirect_get_request_id_header() {
+        let response = get_raw_response(handle("/empty").await);
+        assert_eq!(response.status().as_u16(), 204);
+        assert_eq!(
+            response.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "abc-123"
+        );
+
+        let response = get_raw_response(handle("/redirect").await);
+        assert_eq!(response.status().as_u16(), 302);
+        assert_eq!(response.headers().get("location").unwrap(), "/content");
+        assert_eq!(
+            response.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "abc-123"
+        );
+    }
+}
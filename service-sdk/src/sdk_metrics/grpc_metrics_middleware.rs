@@ -1,10 +1,18 @@
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use http_body::{Body, Frame, SizeHint};
 use my_grpc_extensions::hyper;
 use my_grpc_extensions::tonic::body::BoxBody;
+use my_grpc_extensions::tonic::codegen::http::HeaderMap;
+use my_grpc_extensions::tonic::{Code, Status};
+use stopwatch::Stopwatch;
 use tower::{Layer, Service};
 
+use super::InFlightGaugeGuard;
+
 #[derive(Debug, Clone, Default)]
 pub struct GrpcMetricsMiddlewareLayer;
 
@@ -21,6 +29,9 @@ pub struct GrpcMetricsMiddleware<S> {
     inner: S,
 }
 
+// Reflection calls are made by tools like grpcurl and should not affect service metrics
+const GRPC_REFLECTION_PATH_PREFIX: &str = "/grpc.reflection.";
+
 type BoxFuture<'a, T> = Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
 
 impl<S> Service<hyper::Request<BoxBody>> for GrpcMetricsMiddleware<S>
@@ -45,24 +56,507 @@ where
         let method = req.method().to_string();
         let path = req.uri().path().to_string();
 
+        if path.starts_with(GRPC_REFLECTION_PATH_PREFIX) {
+            return Box::pin(async move { inner.call(req).await });
+        }
+
+        let request_size = Arc::new(AtomicUsize::new(0));
+        let req = req.map(|body| {
+            my_grpc_extensions::tonic::body::boxed(GrpcRequestSizeBody {
+                inner: body,
+                request_size: request_size.clone(),
+            })
+        });
+
+        // Continues the trace of the caller if traceparent header is present
+        #[cfg(feature = "opentelemetry")]
+        let otel_cx = {
+            let trace_headers = crate::TraceHeaders::from_headers(
+                [crate::TRACEPARENT_HEADER, crate::TRACESTATE_HEADER]
+                    .into_iter()
+                    .filter_map(|name| Some((name, req.headers().get(name)?.to_str().ok()?))),
+            );
+            crate::start_grpc_server_span(&path, &trace_headers.get_context())
+        };
+
         Box::pin(async move {
-            let mut sw = stopwatch::Stopwatch::start_new();
+            let in_flight = InFlightGaugeGuard::new(metrics::gauge!("grpc_requests_in_flight"));
+            let sw = Stopwatch::start_new();
+            #[cfg(feature = "opentelemetry")]
+            let response = {
+                use opentelemetry::trace::FutureExt;
+                inner.call(req).with_context(otel_cx.clone()).await?
+            };
+            #[cfg(not(feature = "opentelemetry"))]
             let response = inner.call(req).await?;
-            sw.stop();
-            let duration = sw.elapsed();
-            let common_labels = &[
-                ("method", method),
-                ("path", path),
-                ("status_code", response.status().to_string()),
-            ];
-
-            metrics::histogram!("grpc_request_duration_sec", common_labels)
-                .record(duration.as_secs_f64());
-            metrics::counter!("grpc_request_duration_milis_sum", common_labels)
-                .increment(duration.as_millis() as u64);
-            metrics::counter!("grpc_request_count", common_labels).increment(1);
-
-            Ok(response)
+
+            let mut request_metrics = GrpcRequestMetrics {
+                method,
+                path,
+                sw,
+                request_size,
+                response_size: 0,
+                written: false,
+                _in_flight: in_flight,
+                #[cfg(feature = "opentelemetry")]
+                otel_cx,
+            };
+
+            // Trailers-only response: handler failed before sending any message
+            if let Some(code) = get_grpc_code(response.headers()) {
+                request_metrics.write(code);
+                return Ok(response);
+            }
+
+            Ok(response.map(|body| {
+                my_grpc_extensions::tonic::body::boxed(GrpcMetricsBody {
+                    inner: body,
+                    request_metrics,
+                })
+            }))
+        })
+    }
+}
+
+fn get_grpc_code(headers: &HeaderMap) -> Option<Code> {
+    let value = headers.get("grpc-status")?;
+    let code = value.to_str().ok()?.parse::<i32>().ok()?;
+    Some(Code::from_i32(code))
+}
+
+#[cfg(feature = "opentelemetry")]
+fn is_grpc_server_error(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unknown
+            | Code::DeadlineExceeded
+            | Code::Unimplemented
+            | Code::Internal
+            | Code::Unavailable
+            | Code::DataLoss
+    )
+}
+
+struct GrpcRequestMetrics {
+    method: String,
+    path: String,
+    sw: Stopwatch,
+    request_size: Arc<AtomicUsize>,
+    response_size: usize,
+    written: bool,
+    _in_flight: InFlightGaugeGuard,
+    #[cfg(feature = "opentelemetry")]
+    otel_cx: opentelemetry::Context,
+}
+
+impl GrpcRequestMetrics {
+    fn write(&mut self, code: Code) {
+        if self.written {
+            return;
+        }
+
+        self.written = true;
+        self.sw.stop();
+
+        let duration = self.sw.elapsed();
+        let common_labels = &[
+            ("method", self.method.clone()),
+            ("path", self.path.clone()),
+            ("grpc_code", format!("{:?}", code)),
+        ];
+
+        metrics::histogram!("grpc_request_duration_sec", common_labels)
+            .record(duration.as_secs_f64());
+        metrics::counter!("grpc_request_duration_milis_sum", common_labels)
+            .increment(duration.as_millis() as u64);
+        metrics::counter!("grpc_request_count", common_labels).increment(1);
+
+        if code != Code::Ok {
+            metrics::counter!("grpc_failed_request_count", common_labels).increment(1);
+        }
+
+        let size_labels = &[("method", self.method.clone()), ("path", self.path.clone())];
+
+        metrics::histogram!("grpc_request_size_bytes", size_labels)
+            .record(self.request_size.load(Ordering::Relaxed) as f64);
+        metrics::histogram!("grpc_response_size_bytes", size_labels)
+            .record(self.response_size as f64);
+
+        #[cfg(feature = "opentelemetry")]
+        crate::end_grpc_server_span(&self.otel_cx, code as i32, is_grpc_server_error(code));
+    }
+}
+
+impl Drop for GrpcRequestMetrics {
+    // Body was dropped before trailers were sent, which means the client went away
+    fn drop(&mut self) {
+        self.write(Code::Cancelled);
+    }
+}
+
+// Waits for grpc-status trailer at the end of the stream
+struct GrpcMetricsBody {
+    inner: BoxBody,
+    request_metrics: GrpcRequestMetrics,
+}
+
+impl Body for GrpcMetricsBody {
+    type Data = <BoxBody as Body>::Data;
+    type Error = Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let result = Pin::new(&mut self.inner).poll_frame(cx);
+
+        match &result {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    self.request_metrics.response_size += data.len();
+                }
+
+                if let Some(trailers) = frame.trailers_ref() {
+                    let code = get_grpc_code(trailers).unwrap_or(Code::Unknown);
+                    self.request_metrics.write(code);
+                }
+            }
+            Poll::Ready(Some(Err(status))) => {
+                self.request_metrics.write(status.code());
+            }
+            Poll::Ready(None) => {
+                self.request_metrics.write(Code::Unknown);
+            }
+            Poll::Pending => {}
+        }
+
+        result
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// Counts bytes of the request messages as they are read by the handler
+struct GrpcRequestSizeBody {
+    inner: BoxBody,
+    request_size: Arc<AtomicUsize>,
+}
+
+impl Body for GrpcRequestSizeBody {
+    type Data = <BoxBody as Body>::Data;
+    type Error = Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let result = Pin::new(&mut self.inner).poll_frame(cx);
+
+        if let Poll::Ready(Some(Ok(frame))) = &result {
+            if let Some(data) = frame.data_ref() {
+                self.request_size.fetch_add(data.len(), Ordering::Relaxed);
+            }
+        }
+
+        result
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        convert::Infallible,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use http_body::{Body, Frame};
+    use http_body_util::BodyExt;
+    use my_grpc_extensions::hyper;
+    use my_grpc_extensions::tonic::{
+        body::BoxBody,
+        codegen::{http::HeaderMap, Bytes},
+        Status,
+    };
+    use tower::{Layer, Service};
+
+    use super::GrpcMetricsMiddlewareLayer;
+    use crate::sdk_metrics::TestMetricsRecorder;
+
+    struct TestBody {
+        frames: VecDeque<Frame<Bytes>>,
+    }
+
+    impl Body for TestBody {
+        type Data = Bytes;
+        type Error = Status;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+            Poll::Ready(self.frames.pop_front().map(Ok))
+        }
+    }
+
+    fn create_body(frames: Vec<Frame<Bytes>>) -> BoxBody {
+        my_grpc_extensions::tonic::body::boxed(TestBody {
+            frames: frames.into(),
         })
     }
+
+    fn create_trailers(grpc_status: &str) -> Frame<Bytes> {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", grpc_status.parse().unwrap());
+        Frame::trailers(trailers)
+    }
+
+    const REQUEST_MESSAGE: &[u8] = b"request";
+
+    // Calls the layer with a handler which reads REQUEST_MESSAGE and returns the response built by create_response
+    async fn call(
+        path: &str,
+        create_response: impl Fn() -> hyper::Response<BoxBody> + Clone + Send + 'static,
+    ) -> hyper::Response<BoxBody> {
+        let handler = tower::service_fn(move |req: hyper::Request<BoxBody>| {
+            let create_response = create_response.clone();
+            async move {
+                req.into_body().collect().await.unwrap();
+                Ok::<_, Infallible>(create_response())
+            }
+        });
+
+        let request = hyper::Request::builder()
+            .method("POST")
+            .uri(path)
+            .body(create_body(vec![Frame::data(Bytes::from_static(
+                REQUEST_MESSAGE,
+            ))]))
+            .unwrap();
+
+        GrpcMetricsMiddlewareLayer
+            .layer(handler)
+            .call(request)
+            .await
+            .unwrap()
+    }
+
+    fn get_request_count(recorder: &TestMetricsRecorder, path: &str, grpc_code: &str) -> f64 {
+        recorder
+            .get_value(
+                "grpc_request_count",
+                &[("path", path), ("grpc_code", grpc_code)],
+            )
+            .unwrap_or(0.0)
+    }
+
+    #[test]
+    fn test_trailers_only_response() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            call("/test.Orders/TrailersOnly", || {
+                hyper::Response::builder()
+                    .header("grpc-status", "5")
+                    .body(create_body(vec![]))
+                    .unwrap()
+            })
+            .await;
+        });
+
+        let path = "/test.Orders/TrailersOnly";
+        assert_eq!(get_request_count(&recorder, path, "NotFound"), 1.0);
+        assert_eq!(
+            recorder.get_value("grpc_failed_request_count", &[("path", path)]),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn test_code_is_read_from_trailers() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            let response = call("/test.Orders/Trailers", || {
+                hyper::Response::new(create_body(vec![
+                    Frame::data(Bytes::from_static(b"message")),
+                    create_trailers("0"),
+                ]))
+            })
+            .await;
+
+            // Nothing is written until the stream is read to the end
+            assert_eq!(
+                get_request_count(&recorder, "/test.Orders/Trailers", "Ok"),
+                0.0
+            );
+
+            response.into_body().collect().await.unwrap();
+        });
+
+        let path = "/test.Orders/Trailers";
+        assert_eq!(get_request_count(&recorder, path, "Ok"), 1.0);
+        assert!(recorder
+            .get_value("grpc_failed_request_count", &[("path", path)])
+            .is_none());
+    }
+
+    #[test]
+    fn test_stream_without_trailers_is_unknown() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            let response = call("/test.Orders/NoTrailers", || {
+                hyper::Response::new(create_body(vec![Frame::data(Bytes::from_static(
+                    b"message",
+                ))]))
+            })
+            .await;
+
+            response.into_body().collect().await.unwrap();
+        });
+
+        assert_eq!(
+            get_request_count(&recorder, "/test.Orders/NoTrailers", "Unknown"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_dropped_stream_is_cancelled() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            let response = call("/test.Orders/Dropped", || {
+                hyper::Response::new(create_body(vec![
+                    Frame::data(Bytes::from_static(b"message")),
+                    create_trailers("0"),
+                ]))
+            })
+            .await;
+
+            let mut body = response.into_body();
+            body.frame().await.unwrap().unwrap();
+        });
+
+        let path = "/test.Orders/Dropped";
+        assert_eq!(get_request_count(&recorder, path, "Cancelled"), 1.0);
+        assert_eq!(get_request_count(&recorder, path, "Ok"), 0.0);
+    }
+
+    fn get_in_flight(recorder: &TestMetricsRecorder) -> Option<f64> {
+        recorder.get_value("grpc_requests_in_flight", &[])
+    }
+
+    #[test]
+    fn test_in_flight_gauge_returns_to_zero() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            call("/test.Orders/TrailersOnly", || {
+                hyper::Response::builder()
+                    .header("grpc-status", "5")
+                    .body(create_body(vec![]))
+                    .unwrap()
+            })
+            .await;
+            assert_eq!(get_in_flight(&recorder), Some(0.0));
+
+            let response = call("/test.Orders/Trailers", || {
+                hyper::Response::new(create_body(vec![
+                    Frame::data(Bytes::from_static(b"message")),
+                    create_trailers("0"),
+                ]))
+            })
+            .await;
+
+            // Call is in flight until the response stream is finished
+            assert_eq!(get_in_flight(&recorder), Some(1.0));
+            response.into_body().collect().await.unwrap();
+            assert_eq!(get_in_flight(&recorder), Some(0.0));
+
+            let response = call("/test.Orders/Dropped", || {
+                hyper::Response::new(create_body(vec![
+                    Frame::data(Bytes::from_static(b"message")),
+                    create_trailers("0"),
+                ]))
+            })
+            .await;
+
+            assert_eq!(get_in_flight(&recorder), Some(1.0));
+            drop(response);
+            assert_eq!(get_in_flight(&recorder), Some(0.0));
+        });
+    }
+
+    #[test]
+    fn test_sizes_are_recorded_for_ok_and_failed_calls() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            let response = call("/test.Orders/Ok", || {
+                hyper::Response::new(create_body(vec![
+                    Frame::data(Bytes::from_static(b"first")),
+                    Frame::data(Bytes::from_static(b"second")),
+                    create_trailers("0"),
+                ]))
+            })
+            .await;
+            response.into_body().collect().await.unwrap();
+
+            let response = call("/test.Orders/Failed", || {
+                hyper::Response::new(create_body(vec![
+                    Frame::data(Bytes::from_static(b"partial")),
+                    create_trailers("13"),
+                ]))
+            })
+            .await;
+            response.into_body().collect().await.unwrap();
+
+            call("/test.Orders/TrailersOnly", || {
+                hyper::Response::builder()
+                    .header("grpc-status", "5")
+                    .body(create_body(vec![]))
+                    .unwrap()
+            })
+            .await;
+        });
+
+        let request_size = REQUEST_MESSAGE.len() as f64;
+
+        for (path, response_size) in [
+            ("/test.Orders/Ok", 11.0),
+            ("/test.Orders/Failed", 7.0),
+            ("/test.Orders/TrailersOnly", 0.0),
+        ] {
+            assert_eq!(
+                recorder.get_records("grpc_request_size_bytes", &[("path", path)]),
+                vec![request_size]
+            );
+            assert_eq!(
+                recorder.get_records("grpc_response_size_bytes", &[("path", path)]),
+                vec![response_size]
+            );
+        }
+
+        assert_eq!(
+            get_request_count(&recorder, "/test.Orders/Failed", "Internal"),
+            1.0
+        );
+    }
 }
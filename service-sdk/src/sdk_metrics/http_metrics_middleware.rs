@@ -1,12 +1,21 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware, HttpServerRequestFlow};
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
 use stopwatch::Stopwatch;
 
-pub struct MetricsMiddleware;
+use super::{HttpMetricsPaths, InFlightGaugeGuard};
+
+pub struct MetricsMiddleware {
+    paths: Arc<HttpMetricsPaths>,
+}
 
 impl MetricsMiddleware {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(paths: Arc<HttpMetricsPaths>) -> Self {
+        Self { paths }
     }
 }
 
@@ -19,54 +28,44 @@ impl HttpServerMiddleware for MetricsMiddleware {
     ) -> Result<HttpOkResult, HttpFailResult> {
         let path = ctx.request.http_path.as_str().to_string();
 
-        if path == "/metrics" {
-            let report = prometheus::TextEncoder::new()
-                .encode_to_string(&prometheus::default_registry().gather());
+        let request_size = get_request_size(ctx);
 
-            match report {
-                Ok(report) => {
-                    let response = HttpOutput::as_text(report).into_ok_result(false);
-                    return response;
-                }
-                Err(err) => {
-                    let response =
-                        HttpOutput::as_text(err.to_string()).into_fail_result(502, false);
-                    return response;
-                }
-            }
-        }
+        #[cfg(feature = "opentelemetry")]
+        let otel_cx = start_http_span(ctx, &path);
 
+        let in_flight = InFlightGaugeGuard::new(metrics::gauge!("http_requests_in_flight"));
         let mut sw = Stopwatch::start_new();
+        #[cfg(feature = "opentelemetry")]
+        let result = {
+            use opentelemetry::trace::FutureExt;
+            get_next.next(ctx).with_context(otel_cx.clone()).await
+        };
+        #[cfg(not(feature = "opentelemetry"))]
         let result = get_next.next(ctx).await;
         sw.stop();
+        drop(in_flight);
 
         let duration = sw.elapsed();
-        let method = ctx.request.method.as_str().to_string();
-        let path = ctx.request.http_path.as_str().to_string();
-        let common_labels = &[("method", method.clone()), ("path", path.clone())];
+        let method = metrics::SharedString::from(ctx.request.method.as_str().to_string());
+        let is_not_found = matches!(&result, Err(err) if err.status_code == 404);
+        let path = self.paths.get_path_label(&method, &path, is_not_found);
 
-        let mut has_to_write_metrics = false;
+        #[cfg(feature = "opentelemetry")]
+        crate::end_http_server_span(&otel_cx, &method, &path, get_status_code(&result));
+        let common_labels = &[("method", method.clone()), ("path", path.clone())];
 
         if let Err(result) = &result {
-            if result.status_code == 404 {
-                has_to_write_metrics = true
-            } else {
-                let failed_labels = &[
-                    ("method", method.clone()),
-                    ("path", path.clone()),
-                    ("status_code", result.status_code.to_string()),
-                ];
-
-                metrics::counter!("http_failed_request_count", failed_labels).increment(1);
-                metrics::counter!("http_failed_request_milis_duration_sum", failed_labels)
-                    .increment(duration.as_millis() as u64);
-                metrics::histogram!("http_failed_request_duration_sec", failed_labels)
-                    .record(duration.as_secs_f64());
-            }
-        }
+            let failed_labels = &[
+                ("method", method.clone()),
+                ("path", path.clone()),
+                ("status_code", result.status_code.to_string().into()),
+            ];
 
-        if has_to_write_metrics {
-            return result;
+            metrics::counter!("http_failed_request_count", failed_labels).increment(1);
+            metrics::counter!("http_failed_request_milis_duration_sum", failed_labels)
+                .increment(duration.as_millis() as u64);
+            metrics::histogram!("http_failed_request_duration_sec", failed_labels)
+                .record(duration.as_secs_f64());
         }
 
         metrics::histogram!("http_request_duration_sec", common_labels)
@@ -75,6 +74,278 @@ impl HttpServerMiddleware for MetricsMiddleware {
             .increment(duration.as_millis() as u64);
         metrics::counter!("http_request_count", common_labels).increment(1);
 
+        metrics::histogram!("http_request_size_bytes", common_labels).record(request_size as f64);
+
+        if let Some(response_size) = get_response_size(&result) {
+            metrics::histogram!("http_response_size_bytes", common_labels)
+                .record(response_size as f64);
+        }
+
         return result;
     }
 }
+
+// Continues the trace of the caller if traceparent header is present
+#[cfg(feature = "opentelemetry")]
+fn start_http_span(ctx: &HttpContext, path: &str) -> opentelemetry::Context {
+    let method = ctx.request.method.as_str();
+    let headers = ctx.request.get_headers();
+
+    let trace_headers = crate::TraceHeaders::from_headers(
+        [crate::TRACEPARENT_HEADER, crate::TRACESTATE_HEADER]
+            .into_iter()
+            .filter_map(|name| Some((name, headers.get(name)?.to_str().ok()?))),
+    );
+
+    crate::start_server_span(
+        format!("HTTP {}", method),
+        &trace_headers.get_context(),
+        vec![
+            opentelemetry::KeyValue::new("http.request.method", method.to_string()),
+            opentelemetry::KeyValue::new("url.path", path.to_string()),
+        ],
+    )
+}
+
+// Requests without Content-Length (no body or chunked) are counted as 0
+pub(crate) fn get_request_size(ctx: &HttpContext) -> usize {
+    ctx.request
+        .get_headers()
+        .get("content-length")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+// Status code of the response my-http-server writes for the result
+pub(crate) fn get_status_code(result: &Result<HttpOkResult, HttpFailResult>) -> u16 {
+    match result {
+        Ok(ok_result) => match &ok_result.output {
+            HttpOutput::Empty => 204,
+            HttpOutput::Redirect { permanent, .. } => {
+                if *permanent {
+                    301
+                } else {
+                    302
+                }
+            }
+            HttpOutput::Raw(response) => response.status().as_u16(),
+            _ => 200,
+        },
+        Err(fail_result) => fail_result.status_code,
+    }
+}
+
+// Size is known only for the responses with in-memory content
+pub(crate) fn get_response_size(result: &Result<HttpOkResult, HttpFailResult>) -> Option<usize> {
+    match result {
+        Ok(ok_result) => match &ok_result.output {
+            HttpOutput::Content { content, .. } => Some(content.len()),
+            _ => None,
+        },
+        Err(fail_result) => Some(fail_result.content.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use my_http_server::{
+        HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpRequest, HttpServerMiddleware,
+        HttpServerRequestFlow,
+    };
+    #[cfg(feature = "opentelemetry")]
+    use opentelemetry::trace::{SpanKind, Status};
+
+    use super::MetricsMiddleware;
+    use crate::{HttpMetricsPaths, TestMetricsRecorder};
+
+    #[cfg(feature = "opentelemetry")]
+    const TRACE_ID: &str = "0af7651916cd43dd8448eb211c80319c";
+
+    // Responds with the status code from the last path segment and remembers
+    // the in-flight gauge value seen while the request is handled
+    struct StatusHandler {
+        recorder: Option<TestMetricsRecorder>,
+        in_flight: Mutex<Option<f64>>,
+    }
+
+    impl StatusHandler {
+        fn new(recorder: Option<TestMetricsRecorder>) -> Arc<Self> {
+            Arc::new(Self {
+                recorder,
+                in_flight: Mutex::new(None),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl HttpServerMiddleware for StatusHandler {
+        async fn handle_request(
+            &self,
+            ctx: &mut HttpContext,
+            _get_next: &mut HttpServerRequestFlow,
+        ) -> Result<HttpOkResult, HttpFailResult> {
+            if let Some(recorder) = self.recorder.as_ref() {
+                *self.in_flight.lock().unwrap() =
+                    recorder.get_value("http_requests_in_flight", &[]);
+            }
+
+            let status_code: u16 = ctx
+                .request
+                .http_path
+                .as_str()
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .parse()
+                .unwrap();
+
+            if status_code == 200 {
+                return HttpOutput::as_text("OK".to_string()).into_ok_result(false);
+            }
+
+            HttpOutput::as_text("Failed".to_string()).into_fail_result(status_code, false)
+        }
+    }
+
+    async fn handle(path: &str, headers: hyper::HeaderMap, handler: Arc<StatusHandler>) {
+        let paths = HttpMetricsPaths::new([("GET", "/api/status/{code}")], 10);
+        let mut ctx = HttpContext::new(HttpRequest::new("GET", path, headers));
+        let mut flow = HttpServerRequestFlow::new(vec![
+            Arc::new(MetricsMiddleware::new(Arc::new(paths))),
+            handler,
+        ]);
+
+        let _ = flow.next(&mut ctx).await;
+    }
+
+    fn create_headers(content_length: usize) -> hyper::HeaderMap {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert("content-length", content_length.into());
+        headers
+    }
+
+    #[test]
+    fn test_in_flight_gauge_returns_to_zero() {
+        let recorder = TestMetricsRecorder::new();
+        let handler = StatusHandler::new(Some(recorder.clone()));
+
+        recorder.run(handle(
+            "/api/status/200",
+            create_headers(0),
+            handler.clone(),
+        ));
+        assert_eq!(*handler.in_flight.lock().unwrap(), Some(1.0));
+        assert_eq!(
+            recorder.get_value("http_requests_in_flight", &[]),
+            Some(0.0)
+        );
+
+        recorder.run(handle(
+            "/api/status/503",
+            create_headers(0),
+            handler.clone(),
+        ));
+        assert_eq!(*handler.in_flight.lock().unwrap(), Some(1.0));
+        assert_eq!(
+            recorder.get_value("http_requests_in_flight", &[]),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_sizes_are_recorded_for_content_and_fail_results() {
+        let recorder = TestMetricsRecorder::new();
+
+        recorder.run(async {
+            handle(
+                "/api/status/200",
+                create_headers(5),
+                StatusHandler::new(None),
+            )
+            .await;
+            handle(
+                "/api/status/503",
+                create_headers(7),
+                StatusHandler::new(None),
+            )
+            .await;
+        });
+
+        let labels = [("method", "GET"), ("path", "/api/status/{code}")];
+
+        assert_eq!(
+            recorder.get_records("http_request_size_bytes", &labels),
+            vec![5.0, 7.0]
+        );
+        // "OK" and "Failed" bodies
+        assert_eq!(
+            recorder.get_records("http_response_size_bytes", &labels),
+            vec![2.0, 6.0]
+        );
+        assert_eq!(recorder.get_value("http_request_count", &labels), Some(2.0));
+        assert_eq!(
+            recorder.get_value(
+                "http_failed_request_count",
+                &[("path", "/api/status/{code}"), ("status_code", "503")]
+            ),
+            Some(1.0)
+        );
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    async fn handle_traced(path: &str, span_id: &str) {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            format!("00-{}-{}-01", TRACE_ID, span_id).parse().unwrap(),
+        );
+
+        handle(path, headers, StatusHandler::new(None)).await;
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    fn get_span(
+        exporter: &opentelemetry_sdk::testing::trace::InMemorySpanExporter,
+        parent_span_id: &str,
+    ) -> opentelemetry_sdk::export::trace::SpanData {
+        exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .find(|itm| itm.parent_span_id.to_string() == parent_span_id)
+            .unwrap()
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    #[tokio::test]
+    async fn test_server_span_continues_caller_trace() {
+        let exporter = crate::start_test_tracing();
+
+        handle_traced("/api/status/200", "b7ad6b7169203331").await;
+
+        let span = get_span(&exporter, "b7ad6b7169203331");
+        assert_eq!(span.span_context.trace_id().to_string(), TRACE_ID);
+        assert_eq!(span.span_kind, SpanKind::Server);
+        assert_eq!(span.name, "GET /api/status/{code}");
+        assert_eq!(span.status, Status::Ok);
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    #[tokio::test]
+    async fn test_server_span_status_follows_response_status() {
+        let exporter = crate::start_test_tracing();
+
+        handle_traced("/api/status/404", "00f067aa0ba902b7").await;
+        handle_traced("/api/status/503", "00f067aa0ba902b8").await;
+
+        assert_eq!(get_span(&exporter, "00f067aa0ba902b7").status, Status::Ok);
+        assert_eq!(
+            get_span(&exporter, "00f067aa0ba902b8").status,
+            Status::error("HTTP 503")
+        );
+    }
+}
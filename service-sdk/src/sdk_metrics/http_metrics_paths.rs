@@ -0,0 +1,245 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use metrics::SharedString;
+
+// Label for the requests which did not match any route and ended with 404.
+// They are still counted as failed requests with status_code 404
+pub const UNMATCHED_PATH_LABEL: &str = "unmatched";
+// Label for the paths which came after the limit of distinct values was reached
+pub const OTHER_PATH_LABEL: &str = "other";
+pub const DEFAULT_MAX_PATH_LABEL_VALUES: usize = 500;
+
+struct HttpRouteTemplate {
+    method: String,
+    route: Arc<str>,
+    segments: Vec<String>,
+}
+
+impl HttpRouteTemplate {
+    fn new(method: &str, route: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            route: Arc::from(route),
+            segments: split_path(route).map(|itm| itm.to_string()).collect(),
+        }
+    }
+
+    fn is_matching(&self, method: &str, path_segments: &[&str]) -> bool {
+        if !self.method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+
+        if self.segments.len() != path_segments.len() {
+            return false;
+        }
+
+        self.segments
+            .iter()
+            .zip(path_segments)
+            .all(|(template_segment, path_segment)| {
+                is_placeholder(template_segment)
+                    || template_segment.eq_ignore_ascii_case(path_segment)
+            })
+    }
+
+    fn has_placeholders(&self) -> bool {
+        self.segments.iter().any(|itm| is_placeholder(itm))
+    }
+}
+
+// Turns request paths into low cardinality `path` label values: route template of the
+// matched controller action, `unmatched` for 404s and the raw path for everything else
+// until the limit of distinct values is reached.
+// Routes are the ones registered in HttpServerBuilder and do not change after the server is built
+pub struct HttpMetricsPaths {
+    routes: Vec<HttpRouteTemplate>,
+    max_path_label_values: usize,
+    // Only raw paths are kept here. Route templates are limited by the routes themselves
+    path_label_values: Mutex<HashSet<String>>,
+}
+
+impl HttpMetricsPaths {
+    pub fn new<'s>(
+        routes: impl IntoIterator<Item = (&'s str, &'s str)>,
+        max_path_label_values: usize,
+    ) -> Self {
+        let mut routes: Vec<HttpRouteTemplate> = routes
+            .into_iter()
+            .map(|(method, route)| HttpRouteTemplate::new(method, route))
+            .collect();
+
+        // Same order as ControllersMiddleware looks actions up: routes without placeholders first,
+        // so /api/orders/list wins over /api/orders/{id}, then the rest in registration order.
+        // Sort is stable, so the first registered of /a/{x}/c and /a/b/{y} handles /a/b/c
+        routes.sort_by_key(|itm| itm.has_placeholders());
+
+        Self {
+            routes,
+            max_path_label_values,
+            path_label_values: Mutex::new(HashSet::new()),
+        }
+    }
+
+    // Route template of the controller action which handles the path
+    pub fn find_route(&self, method: &str, path: &str) -> Option<Arc<str>> {
+        let path_segments: Vec<&str> = split_path(path).collect();
+
+        self.routes
+            .iter()
+            .find(|itm| itm.is_matching(method, &path_segments))
+            .map(|itm| itm.route.clone())
+    }
+
+    pub fn get_path_label(&self, method: &str, path: &str, is_not_found: bool) -> SharedString {
+        if let Some(route) = self.find_route(method, path) {
+            return SharedString::from(route);
+        }
+
+        if is_not_found {
+            return SharedString::const_str(UNMATCHED_PATH_LABEL);
+        }
+
+        let mut path_label_values = self.path_label_values.lock().unwrap();
+
+        if path_label_values.contains(path) {
+            return SharedString::from(path.to_string());
+        }
+
+        if path_label_values.len() >= self.max_path_label_values {
+            return SharedString::const_str(OTHER_PATH_LABEL);
+        }
+
+        path_label_values.insert(path.to_string());
+        SharedString::from(path.to_string())
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|itm| !itm.is_empty())
+}
+
+fn is_placeholder(segment: &str) -> bool {
+    segment.starts_with('{') && segment.ends_with('}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpMetricsPaths, OTHER_PATH_LABEL, UNMATCHED_PATH_LABEL};
+
+    fn create_paths(max_path_label_values: usize) -> HttpMetricsPaths {
+        HttpMetricsPaths::new(
+            [
+                ("GET", "/api/orders/{id}"),
+                ("GET", "/api/orders/list"),
+                ("POST", "/api/orders/{id}/items/{item_id}"),
+                ("GET", "/api/isalive"),
+            ],
+            max_path_label_values,
+        )
+    }
+
+    #[test]
+    fn test_placeholders_are_matched() {
+        let paths = create_paths(10);
+
+        assert_eq!(
+            paths.find_route("GET", "/api/orders/123").as_deref(),
+            Some("/api/orders/{id}")
+        );
+        assert_eq!(
+            paths.find_route("POST", "/api/orders/1/items/2").as_deref(),
+            Some("/api/orders/{id}/items/{item_id}")
+        );
+    }
+
+    #[test]
+    fn test_route_without_placeholders_wins() {
+        let paths = create_paths(10);
+
+        assert_eq!(
+            paths.find_route("GET", "/api/orders/list").as_deref(),
+            Some("/api/orders/list")
+        );
+    }
+
+    #[test]
+    fn test_routes_with_placeholders_keep_registration_order() {
+        let paths = HttpMetricsPaths::new([("GET", "/a/{x}/c"), ("GET", "/a/b/{y}")], 10);
+        assert_eq!(
+            paths.find_route("GET", "/a/b/c").as_deref(),
+            Some("/a/{x}/c")
+        );
+
+        let paths = HttpMetricsPaths::new([("GET", "/a/b/{y}"), ("GET", "/a/{x}/c")], 10);
+        assert_eq!(
+            paths.find_route("GET", "/a/b/c").as_deref(),
+            Some("/a/b/{y}")
+        );
+
+        // Number of placeholders does not matter
+        let paths = HttpMetricsPaths::new([("GET", "/a/{x}/{y}"), ("GET", "/a/b/{y}")], 10);
+        assert_eq!(
+            paths.find_route("GET", "/a/b/c").as_deref(),
+            Some("/a/{x}/{y}")
+        );
+    }
+
+    #[test]
+    fn test_method_case_and_slashes() {
+        let paths = create_paths(10);
+
+        assert_eq!(
+            paths.find_route("get", "/API/Orders/123/").as_deref(),
+            Some("/api/orders/{id}")
+        );
+        assert!(paths.find_route("DELETE", "/api/orders/123").is_none());
+        assert!(paths.find_route("GET", "/api/orders").is_none());
+        assert!(paths.find_route("GET", "/api/orders/1/items/2").is_none());
+    }
+
+    #[test]
+    fn test_unmatched_not_found() {
+        let paths = create_paths(10);
+
+        assert_eq!(
+            paths.get_path_label("GET", "/api/unknown", true).as_ref(),
+            UNMATCHED_PATH_LABEL
+        );
+        // Route template is used even if the action itself returned 404
+        assert_eq!(
+            paths.get_path_label("GET", "/api/orders/1", true).as_ref(),
+            "/api/orders/{id}"
+        );
+    }
+
+    #[test]
+    fn test_raw_paths_are_limited() {
+        let paths = create_paths(2);
+
+        assert_eq!(
+            paths.get_path_label("GET", "/swagger", false).as_ref(),
+            "/swagger"
+        );
+        assert_eq!(
+            paths.get_path_label("GET", "/custom", false).as_ref(),
+            "/custom"
+        );
+        assert_eq!(
+            paths.get_path_label("GET", "/another", false).as_ref(),
+            OTHER_PATH_LABEL
+        );
+        // Values which are already used are kept
+        assert_eq!(
+            paths.get_path_label("GET", "/swagger", false).as_ref(),
+            "/swagger"
+        );
+        // Routes do not count against the limit
+        assert_eq!(
+            paths.get_path_label("GET", "/api/orders/1", false).as_ref(),
+            "/api/orders/{id}"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+// Keeps gauge incremented while the request is processed. Decrements on drop,
+// so cancelled requests are not counted forever
+pub struct InFlightGaugeGuard {
+    gauge: metrics::Gauge,
+}
+
+impl InFlightGaugeGuard {
+    pub fn new(gauge: metrics::Gauge) -> Self {
+        gauge.increment(1.0);
+        Self { gauge }
+    }
+}
+
+impl Drop for InFlightGaugeGuard {
+    fn drop(&mut self) {
+        self.gauge.decrement(1.0);
+    }
+}
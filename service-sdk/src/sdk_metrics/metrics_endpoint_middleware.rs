@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use my_http_server::{
+    HttpContext, HttpFailResult, HttpOkResult, HttpOutput, HttpServerMiddleware,
+    HttpServerRequestFlow,
+};
+
+pub const METRICS_PATH: &str = "/metrics";
+
+pub struct MetricsEndpointMiddleware;
+
+impl MetricsEndpointMiddleware {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl HttpServerMiddleware for MetricsEndpointMiddleware {
+    async fn handle_request(
+        &self,
+        ctx: &mut HttpContext,
+        get_next: &mut HttpServerRequestFlow,
+    ) -> Result<HttpOkResult, HttpFailResult> {
+        if ctx.request.http_path.as_str() != METRICS_PATH {
+            return get_next.next(ctx).await;
+        }
+
+        match super::render_metrics() {
+            Ok(report) => HttpOutput::as_text(report).into_ok_result(false),
+            Err(err) => HttpOutput::as_text(err.to_string()).into_fail_result(502, false),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::{OnceLock, RwLock};
+
+use prometheus::proto::{LabelPair, MetricFamily};
+
+static SDK_METRICS_RECORDER: OnceLock<metrics_prometheus::Recorder> = OnceLock::new();
+
+static METRICS_RENDER_OPTIONS: RwLock<MetricsRenderOptions> =
+    RwLock::new(MetricsRenderOptions::new());
+
+// Prefix and constant labels are applied when metrics are rendered, so they can be configured
+// after the recorder is installed and are the same for SDK, custom and other crates metrics
+pub struct MetricsRenderOptions {
+    pub prefix: Option<String>,
+    pub constant_labels: Vec<(String, String)>,
+}
+
+impl MetricsRenderOptions {
+    pub const fn new() -> Self {
+        Self {
+            prefix: None,
+            constant_labels: Vec::new(),
+        }
+    }
+
+    fn apply(&self, metric_family: &mut MetricFamily) {
+        if let Some(prefix) = self.prefix.as_ref() {
+            let name = format!("{}_{}", prefix, metric_family.get_name());
+            metric_family.set_name(name);
+        }
+
+        if self.constant_labels.is_empty() {
+            return;
+        }
+
+        for metric in metric_family.mut_metric().iter_mut() {
+            let labels = metric.mut_label();
+
+            for (name, value) in self.constant_labels.iter() {
+                // Label written with the metric wins
+                if labels.iter().any(|itm| itm.get_name() == name) {
+                    continue;
+                }
+
+                let mut label = LabelPair::new();
+                label.set_name(name.to_string());
+                label.set_value(value.to_string());
+                labels.push(label);
+            }
+
+            labels.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        }
+    }
+}
+
+// Metrics facade writes to prometheus default registry, the same one other crates register
+// their metrics in. Metrics written before the recorder is installed are not collected
+pub fn install_metrics_recorder() -> &'static metrics_prometheus::Recorder {
+    SDK_METRICS_RECORDER.get_or_init(metrics_prometheus::install)
+}
+
+pub fn set_metrics_render_options(render_options: MetricsRenderOptions) {
+    *METRICS_RENDER_OPTIONS.write().unwrap() = render_options;
+}
+
+pub fn render_metrics() -> Result<String, prometheus::Error> {
+    let mut metric_families = prometheus::default_registry().gather();
+
+    let render_options = METRICS_RENDER_OPTIONS.read().unwrap();
+    for metric_family in metric_families.iter_mut() {
+        render_options.apply(metric_family);
+    }
+
+    prometheus::TextEncoder::new().encode_to_string(&metric_families)
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::proto::MetricFamily;
+
+    use super::MetricsRenderOptions;
+
+    fn create_metric_family() -> MetricFamily {
+        let counter = prometheus::IntCounterVec::new(
+            prometheus::Opts::new("http_request_count", "Count of HTTP requests"),
+            &["method", "service_name"],
+        )
+        .unwrap();
+
+        counter.with_label_values(&["GET", "custom"]).inc();
+
+        prometheus::core::Collector::collect(&counter).remove(0)
+    }
+
+    fn get_labels(metric_family: &MetricFamily) -> Vec<(String, String)> {
+        metric_family.get_metric()[0]
+            .get_label()
+            .iter()
+            .map(|itm| (itm.get_name().to_string(), itm.get_value().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_options_keep_metric_as_is() {
+        let mut metric_family = create_metric_family();
+
+        MetricsRenderOptions::new().apply(&mut metric_family);
+
+        assert_eq!(metric_family.get_name(), "http_request_count");
+        assert_eq!(get_labels(&metric_family).len(), 2);
+    }
+
+    #[test]
+    fn test_prefix_and_constant_labels_are_applied() {
+        let mut metric_family = create_metric_family();
+
+        let render_options = MetricsRenderOptions {
+            prefix: Some("orders".to_string()),
+            constant_labels: vec![
+                ("env".to_string(), "prod".to_string()),
+                ("service_name".to_string(), "orders".to_string()),
+            ],
+        };
+
+        render_options.apply(&mut metric_family);
+
+        assert_eq!(metric_family.get_name(), "orders_http_request_count");
+        assert_eq!(
+            get_labels(&metric_family),
+            vec![
+                ("env".to_string(), "prod".to_string()),
+                ("method".to_string(), "GET".to_string()),
+                ("service_name".to_string(), "custom".to_string()),
+            ]
+        );
+    }
+}
@@ -1,7 +1,25 @@
 #[cfg(feature = "grpc")]
 mod grpc_metrics_middleware;
 mod http_metrics_middleware;
+mod http_metrics_paths;
+mod in_flight_gauge_guard;
+mod metrics_endpoint_middleware;
+mod metrics_registry;
+#[cfg(feature = "runtime-metrics")]
+mod runtime_metrics;
+mod sdk_metrics_config;
+#[cfg(test)]
+mod test_metrics_recorder;
 
 #[cfg(feature = "grpc")]
 pub use grpc_metrics_middleware::*;
 pub use http_metrics_middleware::*;
+pub use http_metrics_paths::*;
+pub use in_flight_gauge_guard::*;
+pub use metrics_endpoint_middleware::*;
+pub use metrics_registry::*;
+#[cfg(feature = "runtime-metrics")]
+pub use runtime_metrics::*;
+pub use sdk_metrics_config::*;
+#[cfg(test)]
+pub(crate) use test_metrics_recorder::*;
@@ -0,0 +1,171 @@
+use std::time::{Duration, Instant};
+
+const RUNTIME_METRICS_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Process collector exports RSS, virtual memory, CPU seconds, open and max fds, threads and start time.
+// prometheus reads them from /proc, so there is no process collector on other platforms
+#[cfg(target_os = "linux")]
+pub fn register_process_metrics(registry: &prometheus::Registry) {
+    let process_collector = prometheus::process_collector::ProcessCollector::for_self();
+
+    if let Err(err) = registry.register(Box::new(process_collector)) {
+        my_logger::LOGGER.write_warning(
+            "Runtime metrics".to_string(),
+            format!("Can not register process metrics. {}", err),
+            my_logger::LogEventCtx::new(),
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn register_process_metrics(_registry: &prometheus::Registry) {}
+
+// Called when log sinks are plugged, so the warning is not lost
+pub fn warn_if_runtime_metrics_are_missing() {
+    #[cfg(not(tokio_unstable))]
+    my_logger::LOGGER.write_warning(
+        "Runtime metrics".to_string(),
+        "Built without RUSTFLAGS=\"--cfg tokio_unstable\", so tokio_spawned_tasks_count and tokio_blocking_threads_count are not exported".to_string(),
+        my_logger::LogEventCtx::new(),
+    );
+}
+
+// Samples runtime of the caller. Has to be called inside tokio runtime
+pub fn start_runtime_metrics_sampling() {
+    let mut writer = RuntimeMetricsWriter::new(tokio::runtime::Handle::current());
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(RUNTIME_METRICS_SAMPLE_INTERVAL).await;
+            writer.write();
+        }
+    });
+}
+
+struct RuntimeMetricsWriter {
+    runtime_metrics: tokio::runtime::RuntimeMetrics,
+    busy_ratio_sampler: BusyRatioSampler,
+    started: Instant,
+}
+
+impl RuntimeMetricsWriter {
+    fn new(handle: tokio::runtime::Handle) -> Self {
+        let runtime_metrics = handle.metrics();
+
+        Self {
+            busy_ratio_sampler: BusyRatioSampler::new(&runtime_metrics),
+            runtime_metrics,
+            started: Instant::now(),
+        }
+    }
+
+    fn write(&mut self) {
+        let runtime_metrics = &self.runtime_metrics;
+
+        metrics::gauge!("process_uptime_seconds").set(self.started.elapsed().as_secs_f64());
+        metrics::gauge!("tokio_workers_count").set(runtime_metrics.num_workers() as f64);
+        metrics::gauge!("tokio_global_queue_depth")
+            .set(runtime_metrics.global_queue_depth() as f64);
+        metrics::gauge!("tokio_alive_tasks_count").set(runtime_metrics.num_alive_tasks() as f64);
+
+        if let Some(busy_ratio) = self.busy_ratio_sampler.sample(runtime_metrics) {
+            metrics::gauge!("tokio_worker_busy_ratio").set(busy_ratio);
+        }
+
+        // Available only when the service is built with RUSTFLAGS="--cfg tokio_unstable"
+        #[cfg(tokio_unstable)]
+        metrics::gauge!("tokio_blocking_threads_count")
+            .set(runtime_metrics.num_blocking_threads() as f64);
+
+        #[cfg(all(tokio_unstable, target_has_atomic = "64"))]
+        metrics::gauge!("tokio_spawned_tasks_count")
+            .set(runtime_metrics.spawned_tasks_count() as f64);
+    }
+}
+
+// Worker busy duration is exported by tokio only on platforms with 64 bit atomics
+struct BusyRatioSampler {
+    #[cfg(target_has_atomic = "64")]
+    last_busy_duration: Duration,
+    #[cfg(target_has_atomic = "64")]
+    last_sample: Instant,
+}
+
+impl BusyRatioSampler {
+    #[cfg(target_has_atomic = "64")]
+    fn new(runtime_metrics: &tokio::runtime::RuntimeMetrics) -> Self {
+        Self {
+            last_busy_duration: get_total_busy_duration(runtime_metrics),
+            last_sample: Instant::now(),
+        }
+    }
+
+    #[cfg(not(target_has_atomic = "64"))]
+    fn new(_runtime_metrics: &tokio::runtime::RuntimeMetrics) -> Self {
+        Self {}
+    }
+
+    // Share of the time workers were busy since previous sample. 1.0 means all workers were busy
+    #[cfg(target_has_atomic = "64")]
+    fn sample(&mut self, runtime_metrics: &tokio::runtime::RuntimeMetrics) -> Option<f64> {
+        let busy_duration = get_total_busy_duration(runtime_metrics);
+        let elapsed = self.last_sample.elapsed();
+
+        let busy_ratio = (busy_duration - self.last_busy_duration).as_secs_f64()
+            / (elapsed.as_secs_f64() * runtime_metrics.num_workers() as f64);
+
+        self.last_busy_duration = busy_duration;
+        self.last_sample = Instant::now();
+
+        Some(busy_ratio)
+    }
+
+    #[cfg(not(target_has_atomic = "64"))]
+    fn sample(&mut self, _runtime_metrics: &tokio::runtime::RuntimeMetrics) -> Option<f64> {
+        None
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+fn get_total_busy_duration(runtime_metrics: &tokio::runtime::RuntimeMetrics) -> Duration {
+    (0..runtime_metrics.num_workers())
+        .map(|worker| runtime_metrics.worker_total_busy_duration(worker))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RuntimeMetricsWriter;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_process_and_tokio_metrics_are_rendered() {
+        crate::install_metrics_recorder();
+        super::register_process_metrics(prometheus::default_registry());
+
+        RuntimeMetricsWriter::new(tokio::runtime::Handle::current()).write();
+
+        let report = crate::render_metrics().unwrap();
+
+        #[cfg(target_os = "linux")]
+        for name in [
+            "process_resident_memory_bytes",
+            "process_cpu_seconds_total",
+            "process_open_fds",
+            "process_threads",
+        ] {
+            assert!(report.contains(name), "{} is not rendered", name);
+        }
+
+        for name in [
+            "process_uptime_seconds",
+            "tokio_workers_count 2",
+            "tokio_global_queue_depth",
+            "tokio_alive_tasks_count",
+        ] {
+            assert!(report.contains(name), "{} is not rendered", name);
+        }
+
+        #[cfg(tokio_unstable)]
+        assert!(report.contains("tokio_blocking_threads_count"));
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use super::{install_metrics_recorder, set_metrics_render_options, MetricsRenderOptions};
+
+pub const SERVICE_NAME_LABEL: &str = "service_name";
+pub const SERVICE_VERSION_LABEL: &str = "service_version";
+pub const POD_NAME_LABEL: &str = "pod_name";
+
+// Label names of the histograms written by SDK. Prometheus needs them to register a histogram upfront
+const SDK_HISTOGRAMS: &[(&str, &[&str])] = &[
+    ("http_request_duration_sec", &["method", "path"]),
+    (
+        "http_failed_request_duration_sec",
+        &["method", "path", "status_code"],
+    ),
+    ("http_request_size_bytes", &["method", "path"]),
+    ("http_response_size_bytes", &["method", "path"]),
+    (
+        "grpc_request_duration_sec",
+        &["method", "path", "grpc_code"],
+    ),
+    ("grpc_request_size_bytes", &["method", "path"]),
+    ("grpc_response_size_bytes", &["method", "path"]),
+];
+
+struct HistogramBuckets {
+    label_names: Vec<String>,
+    buckets: Vec<f64>,
+}
+
+pub struct SdkMetricsConfig {
+    prefix: Option<String>,
+    constant_labels: HashMap<String, String>,
+    add_service_labels: bool,
+    histogram_buckets: HashMap<String, HistogramBuckets>,
+}
+
+impl SdkMetricsConfig {
+    pub fn new() -> Self {
+        Self {
+            prefix: None,
+            constant_labels: HashMap::new(),
+            add_service_labels: false,
+            histogram_buckets: HashMap::new(),
+        }
+    }
+
+    // Prefix is joined with "_": orders -> orders_http_request_count
+    pub fn set_prefix(&mut self, prefix: impl Into<String>) -> &mut Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn add_constant_label(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> &mut Self {
+        self.constant_labels.insert(name.into(), value.into());
+        self
+    }
+
+    // service_name and service_version from ServiceInfo, pod_name from POD_NAME or HOSTNAME env variable
+    pub fn add_service_labels(&mut self) -> &mut Self {
+        self.add_service_labels = true;
+        self
+    }
+
+    pub fn set_histogram_buckets(
+        &mut self,
+        name: &str,
+        buckets: Vec<f64>,
+    ) -> Result<&mut Self, String> {
+        let Some((_, label_names)) = SDK_HISTOGRAMS.iter().find(|(itm, _)| *itm == name) else {
+            return Err(format!(
+                "{} is not SDK histogram. Use set_custom_histogram_buckets with label names",
+                name
+            ));
+        };
+
+        Ok(self.set_custom_histogram_buckets(name, label_names, buckets))
+    }
+
+    // Label names have to be the same as the ones the histogram is written with
+    pub fn set_custom_histogram_buckets(
+        &mut self,
+        name: &str,
+        label_names: &[&str],
+        buckets: Vec<f64>,
+    ) -> &mut Self {
+        self.histogram_buckets.insert(
+            name.to_string(),
+            HistogramBuckets {
+                label_names: label_names.iter().map(|itm| itm.to_string()).collect(),
+                buckets,
+            },
+        );
+        self
+    }
+
+    // Histograms have to be registered before they are written for the first time,
+    // so it is called before any server is started
+    pub fn apply(&self, app_name: &str, app_version: &str) -> Result<(), String> {
+        if let Some(prefix) = self.prefix.as_ref() {
+            if !is_valid_name(prefix) {
+                return Err(format!("Invalid metrics prefix {}", prefix));
+            }
+        }
+
+        let mut constant_labels = self.constant_labels.clone();
+
+        if self.add_service_labels {
+            constant_labels.insert(SERVICE_NAME_LABEL.to_string(), app_name.to_string());
+            constant_labels.insert(SERVICE_VERSION_LABEL.to_string(), app_version.to_string());
+
+            if let Some(pod_name) = get_pod_name() {
+                constant_labels.insert(POD_NAME_LABEL.to_string(), pod_name);
+            }
+        }
+
+        if let Some(name) = constant_labels.keys().find(|itm| !is_valid_name(itm)) {
+            return Err(format!("Invalid constant label name {}", name));
+        }
+
+        let recorder = install_metrics_recorder();
+
+        for (name, histogram_buckets) in self.histogram_buckets.iter() {
+            let opts = prometheus::HistogramOpts::new(name.as_str(), name.as_str())
+                .buckets(histogram_buckets.buckets.clone());
+            let label_names: Vec<&str> = histogram_buckets
+                .label_names
+                .iter()
+                .map(|itm| itm.as_str())
+                .collect();
+
+            let histogram = prometheus::HistogramVec::new(opts, &label_names)
+                .map_err(|err| format!("Invalid buckets for histogram {}. {}", name, err))?;
+
+            recorder
+                .try_register_metric(histogram)
+                .map_err(|err| format!("Can not register histogram {}. {}", name, err))?;
+        }
+
+        set_metrics_render_options(MetricsRenderOptions {
+            prefix: self.prefix.clone(),
+            constant_labels: constant_labels.into_iter().collect(),
+        });
+
+        Ok(())
+    }
+}
+
+fn get_pod_name() -> Option<String> {
+    if let Ok(pod_name) = std::env::var("POD_NAME") {
+        return Some(pod_name);
+    }
+
+    std::env::var("HOSTNAME").ok()
+}
+
+// Metric names and label names: letters, digits and underscores, not starting with a digit
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    let Some(first) = chars.next() else {
+        return false;
+    };
+
+    (first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|itm| itm.is_ascii_alphanumeric() || itm == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SdkMetricsConfig;
+
+    #[test]
+    fn test_unknown_sdk_histogram_is_error() {
+        let mut config = SdkMetricsConfig::new();
+
+        assert!(config
+            .set_histogram_buckets("http_request_duration_sec", vec![0.1, 1.0])
+            .is_ok());
+        assert!(config
+            .set_histogram_buckets("my_histogram", vec![0.1, 1.0])
+            .is_err());
+    }
+
+    #[test]
+    fn test_names_are_validated() {
+        assert!(super::is_valid_name("orders"));
+        assert!(super::is_valid_name("_orders_2"));
+        assert!(!super::is_valid_name(""));
+        assert!(!super::is_valid_name("2orders"));
+        assert!(!super::is_valid_name("orders-api"));
+    }
+}
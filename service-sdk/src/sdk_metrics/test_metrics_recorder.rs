@@ -0,0 +1,131 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+
+type TestMetrics = Vec<(Key, Arc<TestMetric>)>;
+
+// Keeps the metrics written inside run, so tests do not depend on the global recorder
+#[derive(Clone)]
+pub(crate) struct TestMetricsRecorder {
+    metrics: Arc<Mutex<TestMetrics>>,
+}
+
+#[derive(Default)]
+struct TestMetric {
+    value: Mutex<f64>,
+    records: Mutex<Vec<f64>>,
+}
+
+impl CounterFn for TestMetric {
+    fn increment(&self, value: u64) {
+        *self.value.lock().unwrap() += value as f64;
+    }
+
+    fn absolute(&self, value: u64) {
+        *self.value.lock().unwrap() = value as f64;
+    }
+}
+
+impl GaugeFn for TestMetric {
+    fn increment(&self, value: f64) {
+        *self.value.lock().unwrap() += value;
+    }
+
+    fn decrement(&self, value: f64) {
+        *self.value.lock().unwrap() -= value;
+    }
+
+    fn set(&self, value: f64) {
+        *self.value.lock().unwrap() = value;
+    }
+}
+
+impl HistogramFn for TestMetric {
+    fn record(&self, value: f64) {
+        self.records.lock().unwrap().push(value);
+    }
+}
+
+impl TestMetricsRecorder {
+    pub fn new() -> Self {
+        Self {
+            metrics: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    // Future is driven on the current thread, where the recorder is installed
+    pub fn run<TResult>(&self, future: impl Future<Output = TResult>) -> TResult {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        metrics::with_local_recorder(self, || runtime.block_on(future))
+    }
+
+    // Value of the counter or gauge which has all the labels
+    pub fn get_value(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        self.find(name, labels)
+            .map(|metric| *metric.value.lock().unwrap())
+    }
+
+    // Values recorded to the histogram which has all the labels
+    pub fn get_records(&self, name: &str, labels: &[(&str, &str)]) -> Vec<f64> {
+        self.find(name, labels)
+            .map(|metric| metric.records.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
+    fn find(&self, name: &str, labels: &[(&str, &str)]) -> Option<Arc<TestMetric>> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(key, _)| {
+                key.name() == name
+                    && labels.iter().all(|(label, value)| {
+                        key.labels()
+                            .any(|itm| itm.key() == *label && itm.value() == *value)
+                    })
+            })
+            .map(|(_, metric)| metric.clone())
+    }
+
+    fn register(&self, key: &Key) -> Arc<TestMetric> {
+        let mut metrics = self.metrics.lock().unwrap();
+
+        if let Some((_, metric)) = metrics.iter().find(|(itm, _)| itm == key) {
+            return metric.clone();
+        }
+
+        let metric = Arc::new(TestMetric::default());
+        metrics.push((key.clone(), metric.clone()));
+        metric
+    }
+}
+
+impl Recorder for TestMetricsRecorder {
+    fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.register(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.register(key))
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.register(key))
+    }
+}
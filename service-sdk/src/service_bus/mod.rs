@@ -0,0 +1,9 @@
+#[cfg(feature = "opentelemetry")]
+mod sb_publisher;
+#[cfg(feature = "opentelemetry")]
+mod sb_tracing_callback;
+
+#[cfg(feature = "opentelemetry")]
+pub use sb_publisher::*;
+#[cfg(feature = "opentelemetry")]
+pub use sb_tracing_callback::*;
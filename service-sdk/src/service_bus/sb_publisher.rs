@@ -0,0 +1,50 @@
+use std::ops::Deref;
+
+use my_service_bus::abstractions::{
+    publisher::MyServiceBusPublisher, MySbMessageSerializer, PublishError, SbMessageHeaders,
+};
+
+// Publisher returned by ServiceContext::get_sb_publisher with opentelemetry feature. Trace context
+// of the current span is added to the headers of the message, so the subscriber continues the trace.
+// Other publisher methods are available through Deref and are published as is
+pub struct SbPublisher<TModel: MySbMessageSerializer> {
+    publisher: MyServiceBusPublisher<TModel>,
+}
+
+impl<TModel: MySbMessageSerializer> SbPublisher<TModel> {
+    pub fn new(publisher: MyServiceBusPublisher<TModel>) -> Self {
+        Self { publisher }
+    }
+
+    pub async fn publish(&self, message: &TModel) -> Result<(), PublishError> {
+        self.publisher
+            .publish_with_headers(message, with_trace_headers(SbMessageHeaders::new()))
+            .await
+    }
+
+    pub async fn publish_with_headers(
+        &self,
+        message: &TModel,
+        headers: SbMessageHeaders,
+    ) -> Result<(), PublishError> {
+        self.publisher
+            .publish_with_headers(message, with_trace_headers(headers))
+            .await
+    }
+}
+
+impl<TModel: MySbMessageSerializer> Deref for SbPublisher<TModel> {
+    type Target = MyServiceBusPublisher<TModel>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.publisher
+    }
+}
+
+fn with_trace_headers(mut headers: SbMessageHeaders) -> SbMessageHeaders {
+    for (name, value) in crate::inject_trace_headers() {
+        headers = headers.add(name, value);
+    }
+
+    headers
+}
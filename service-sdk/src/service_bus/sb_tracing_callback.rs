@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use my_service_bus::abstractions::{
+    subscriber::{
+        MessagesReader, MySbMessageDeserializer, MySbSubscriberHandleError, SubscriberCallback,
+    },
+    GetMySbModelTopicId, SbMessageHeaders,
+};
+use opentelemetry::{trace::FutureExt, Context};
+
+// Wraps callbacks registered with ServiceContext::register_sb_subscribe. Batch is handled inside
+// a consumer span which continues the trace of the publisher of the first message in the batch
+pub struct SbTracingCallback<TModel: MySbMessageDeserializer<Item = TModel>> {
+    queue_id: String,
+    callback: Arc<dyn SubscriberCallback<TModel> + Send + Sync + 'static>,
+}
+
+impl<TModel: MySbMessageDeserializer<Item = TModel>> SbTracingCallback<TModel> {
+    pub fn new(
+        queue_id: String,
+        callback: Arc<dyn SubscriberCallback<TModel> + Send + Sync + 'static>,
+    ) -> Self {
+        Self { queue_id, callback }
+    }
+}
+
+#[async_trait]
+impl<
+        TModel: GetMySbModelTopicId + MySbMessageDeserializer<Item = TModel> + Send + Sync + 'static,
+    > SubscriberCallback<TModel> for SbTracingCallback<TModel>
+{
+    async fn handle_messages(
+        &self,
+        messages_reader: &mut MessagesReader<TModel>,
+    ) -> Result<(), MySbSubscriberHandleError> {
+        // Reading headers does not move the reader, so the callback gets all the messages
+        let parent = match messages_reader.peek_next_message() {
+            Some(message) => get_publisher_context(&message.headers),
+            None => Context::new(),
+        };
+
+        let cx = crate::start_sb_consumer_span(TModel::get_topic_id(), &self.queue_id, &parent);
+
+        let result = self
+            .callback
+            .handle_messages(messages_reader)
+            .with_context(cx.clone())
+            .await;
+
+        crate::end_sb_consumer_span(&cx, result.is_err());
+
+        result
+    }
+}
+
+fn get_publisher_context(headers: &SbMessageHeaders) -> Context {
+    crate::extract_trace_context(
+        [crate::TRACEPARENT_HEADER, crate::TRACESTATE_HEADER]
+            .into_iter()
+            .filter_map(|name| Some((name, headers.get(name)?))),
+    )
+}
@@ -1,5 +1,7 @@
 use my_http_server::MyHttpServer;
+#[cfg(feature = "seq")]
 use my_logger::my_seq_logger::{SeqLogger, SeqSettings};
+use my_logger::MyLoggerReader;
 use rust_extensions::{AppStates, MyTimer, StrOrString};
 
 #[cfg(feature = "my-nosql-data-writer-sdk")]
@@ -20,7 +22,6 @@ use serde::de::DeserializeOwned;
 #[cfg(feature = "my-service-bus")]
 use my_service_bus::{
     abstractions::{
-        publisher::MyServiceBusPublisher,
         subscriber::{MySbMessageDeserializer, SubscriberCallback, TopicQueueType},
         GetMySbModelTopicId, MySbMessageSerializer,
     },
@@ -29,18 +30,55 @@ use my_service_bus::{
 
 use std::{sync::Arc, time::Duration};
 
-use crate::{HttpServerBuilder, ServiceInfo};
+use crate::{
+    AdminServerBuilder, BuildInfo, DrainMiddleware, HealthCheck, HealthCheckMiddleware,
+    HealthCheckRegistry, HttpServerBuilder, InFlightRequests, JsonConsoleLogger, LogLevel,
+    LogLevelFilterReader, LogOutputs, ProbesMiddleware, ReadinessCheck, ReadinessProbe,
+    RequestIdLogReader, SdkMetricsConfig, ServiceInfo, ServiceVersion, VersionMiddleware,
+    LOG_LEVEL_ENV, LOG_OUTPUT_ENV, PROCESS_LOG_LEVELS_ENV,
+};
+
+#[cfg(feature = "my-nosql-data-reader-sdk")]
+use crate::{NoSqlHealthCheck, NoSqlReadinessCheck};
+
+#[cfg(feature = "my-service-bus")]
+use crate::{SbHealthCheck, SbReadinessCheck};
+
+#[cfg(all(feature = "my-service-bus", feature = "opentelemetry"))]
+use crate::SbPublisher;
+
+#[cfg(all(feature = "my-service-bus", not(feature = "opentelemetry")))]
+use my_service_bus::abstractions::publisher::MyServiceBusPublisher;
 
 #[cfg(feature = "grpc")]
 use crate::{GrpcServer, GrpcServerBuilder};
 
+const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_SHUTDOWN_PRE_DRAIN_DELAY: Duration = Duration::from_secs(5);
+const HEALTH_CHECK_METRICS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
 pub struct ServiceContext {
     pub http_server_builder: HttpServerBuilder,
     pub http_server: Option<MyHttpServer>,
+    pub admin_server_builder: Option<AdminServerBuilder>,
+    pub admin_server: Option<MyHttpServer>,
     pub app_states: Arc<AppStates>,
     pub app_name: StrOrString<'static>,
     pub app_version: StrOrString<'static>,
     pub background_timers: Vec<MyTimer>,
+    pub in_flight_requests: Arc<InFlightRequests>,
+    pub shutdown_drain_timeout: Duration,
+    pub shutdown_pre_drain_delay: Duration,
+    pub readiness_probe: Arc<ReadinessProbe>,
+    pub health_check_registry: Arc<HealthCheckRegistry>,
+    pub metrics_config: SdkMetricsConfig,
+    pub build_info: BuildInfo,
+    #[cfg(feature = "opentelemetry")]
+    pub otlp_endpoint: Option<String>,
+    #[cfg(feature = "opentelemetry")]
+    pub tracer_provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+    #[cfg(feature = "my-nosql-data-reader-sdk")]
+    pub no_sql_readiness_check: Arc<NoSqlReadinessCheck>,
     #[cfg(feature = "my-nosql-data-reader-sdk")]
     pub my_no_sql_connection: Arc<MyNoSqlTcpConnection>,
     #[cfg(feature = "my-service-bus")]
@@ -53,9 +91,15 @@ pub struct ServiceContext {
 
 impl ServiceContext {
     pub async fn new(settings_reader: service_sdk_macros::generate_settings_signature!()) -> Self {
-        metrics_prometheus::install();
+        crate::install_metrics_recorder();
+
+        #[cfg(feature = "runtime-metrics")]
+        {
+            crate::register_process_metrics(prometheus::default_registry());
+            crate::start_runtime_metrics_sampling();
+        }
 
-        #[cfg(feature = "grpc-with-tls")]
+        #[cfg(any(feature = "grpc-with-tls", feature = "http-tls"))]
         rustls::crypto::ring::default_provider()
             .install_default()
             .expect("Failed to install rustls crypto provider");
@@ -68,7 +112,39 @@ impl ServiceContext {
             .populate_app_and_version(app_name.clone(), app_version.clone())
             .await;
 
-        SeqLogger::enable_from_connection_string(settings_reader.clone());
+        let log_level =
+            crate::get_log_setting(LOG_LEVEL_ENV, settings_reader.get_log_level().await);
+        let process_log_levels = crate::get_log_setting(
+            PROCESS_LOG_LEVELS_ENV,
+            settings_reader.get_process_log_levels().await,
+        );
+
+        crate::LOG_LEVEL_FILTER
+            .configure(log_level.as_deref(), process_log_levels.as_deref())
+            .unwrap_or_else(|err| panic!("Invalid log level configuration. {}", err));
+
+        let log_output =
+            crate::get_log_setting(LOG_OUTPUT_ENV, settings_reader.get_log_output().await);
+        let log_outputs = LogOutputs::from_value(log_output.as_deref())
+            .unwrap_or_else(|err| panic!("Invalid log output configuration. {}", err));
+
+        // Seq plugs its own reader into my_logger::LOGGER, so plug_log_sink does not apply to it
+        #[cfg(feature = "seq")]
+        if log_outputs.seq {
+            // Empty connection string is the way to run without Seq in local dev and tests
+            if settings_reader.get_conn_string().await.trim().is_empty() {
+                println!("Seq connection string is empty. Seq logger is disabled");
+            } else {
+                SeqLogger::enable_from_connection_string(settings_reader.clone());
+            }
+        }
+
+        if log_outputs.json_console {
+            plug_log_sink(Arc::new(JsonConsoleLogger::new(
+                app_name.as_str(),
+                app_version.as_str(),
+            )));
+        }
 
         #[cfg(feature = "my-nosql-data-reader-sdk")]
         let my_no_sql_connection = Arc::new(MyNoSqlTcpConnection::new(
@@ -84,11 +160,43 @@ impl ServiceContext {
             my_logger::LOGGER.clone(),
         ));
 
+        let in_flight_requests = Arc::new(InFlightRequests::new());
+        let readiness_probe = Arc::new(ReadinessProbe::new(app_states.clone()));
+
+        #[cfg(feature = "my-nosql-data-reader-sdk")]
+        let no_sql_readiness_check = Arc::new(NoSqlReadinessCheck::new());
+        #[cfg(feature = "my-nosql-data-reader-sdk")]
+        readiness_probe.register_check(no_sql_readiness_check.clone());
+
+        #[cfg(feature = "my-service-bus")]
+        readiness_probe.register_check(Arc::new(SbReadinessCheck::new(sb_client.clone())));
+
+        let health_check_registry = Arc::new(HealthCheckRegistry::new());
+
+        #[cfg(feature = "my-nosql-data-reader-sdk")]
+        health_check_registry.register_check(Arc::new(NoSqlHealthCheck::new(
+            my_no_sql_connection.clone(),
+        )));
+
+        #[cfg(feature = "my-service-bus")]
+        health_check_registry.register_check(Arc::new(SbHealthCheck::new(sb_client.clone())));
+
+        let mut http_server_builder = HttpServerBuilder::new(app_name.clone(), app_version.clone());
+        http_server_builder.set_drain_middleware(DrainMiddleware::new(
+            app_states.clone(),
+            in_flight_requests.clone(),
+        ));
+        http_server_builder.set_probes_middleware(ProbesMiddleware::new(readiness_probe.clone()));
+        http_server_builder
+            .set_health_check_middleware(HealthCheckMiddleware::new(health_check_registry.clone()));
+
         println!("Initialized service context");
 
         Self {
-            http_server_builder: HttpServerBuilder::new(app_name.clone(), app_version.clone()),
+            http_server_builder,
             http_server: None,
+            admin_server_builder: None,
+            admin_server: None,
             app_states,
             #[cfg(feature = "my-nosql-data-reader-sdk")]
             my_no_sql_connection,
@@ -99,6 +207,19 @@ impl ServiceContext {
             #[cfg(feature = "grpc")]
             grpc_server_builder: None,
             background_timers: vec![],
+            in_flight_requests,
+            shutdown_drain_timeout: DEFAULT_SHUTDOWN_DRAIN_TIMEOUT,
+            shutdown_pre_drain_delay: DEFAULT_SHUTDOWN_PRE_DRAIN_DELAY,
+            readiness_probe,
+            health_check_registry,
+            metrics_config: SdkMetricsConfig::new(),
+            build_info: BuildInfo::unknown(),
+            #[cfg(feature = "opentelemetry")]
+            otlp_endpoint: None,
+            #[cfg(feature = "opentelemetry")]
+            tracer_provider: None,
+            #[cfg(feature = "my-nosql-data-reader-sdk")]
+            no_sql_readiness_check,
             #[cfg(feature = "grpc")]
             grpc_server: None,
         }
@@ -111,12 +232,133 @@ impl ServiceContext {
         self.background_timers.push(timer);
     }
 
+    pub fn register_readiness_check(
+        &mut self,
+        check: Arc<dyn ReadinessCheck + Send + Sync + 'static>,
+    ) -> &mut Self {
+        self.readiness_probe.register_check(check);
+        self
+    }
+
+    pub fn register_health_check(
+        &mut self,
+        check: Arc<dyn HealthCheck + Send + Sync + 'static>,
+    ) -> &mut Self {
+        self.health_check_registry.register_check(check);
+        self
+    }
+
+    pub fn set_health_check_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.health_check_registry.set_check_timeout(timeout);
+        self
+    }
+
+    pub fn set_shutdown_drain_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.shutdown_drain_timeout = timeout;
+        self
+    }
+
+    // Time between SIGTERM and rejecting new requests. Readiness fails during it, so the
+    // pod is removed from the endpoints before it stops accepting traffic
+    pub fn set_shutdown_pre_drain_delay(&mut self, delay: Duration) -> &mut Self {
+        self.shutdown_pre_drain_delay = delay;
+        self
+    }
+
+    // /metrics moves from the public http server to the admin one. Probes and health are served by both
+    pub fn enable_admin_server(&mut self, port: u16) -> &mut Self {
+        self.admin_server_builder = Some(AdminServerBuilder::new(
+            port,
+            self.app_name.clone(),
+            self.app_version.clone(),
+            self.readiness_probe.clone(),
+            self.health_check_registry.clone(),
+        ));
+        self.http_server_builder.disable_metrics_endpoint();
+        self
+    }
+
+    // Does nothing if enable_admin_server was not called, so the same code works with the admin
+    // server switched off by a setting
+    pub fn configure_admin_server(
+        &mut self,
+        config: impl Fn(&mut AdminServerBuilder),
+    ) -> &mut Self {
+        if let Some(admin_server_builder) = self.admin_server_builder.as_mut() {
+            config(admin_server_builder);
+        }
+
+        self
+    }
+
     pub fn configure_http_server(&mut self, config: impl Fn(&mut HttpServerBuilder)) -> &mut Self {
         config(&mut self.http_server_builder);
         self
     }
 
+    // Minimum level of the events written by SDK log sinks. Overrides LOG_LEVEL env variable and settings
+    pub fn set_log_level(&mut self, level: LogLevel) -> &mut Self {
+        crate::LOG_LEVEL_FILTER.set_level(level);
+        self
+    }
+
+    // Process is the first argument of LOGGER.write_*
+    pub fn set_process_log_level(&mut self, process: &str, level: LogLevel) -> &mut Self {
+        crate::LOG_LEVEL_FILTER.set_process_level(process, level);
+        self
+    }
+
+    // service_context.set_build_info(service_sdk::macros::build_info!())
+    pub fn set_build_info(&mut self, build_info: BuildInfo) -> &mut Self {
+        self.build_info = build_info;
+        self
+    }
+
+    // OTLP/gRPC collector, e.g. http://otel-collector:4317. OTEL_EXPORTER_OTLP_ENDPOINT env variable wins
+    #[cfg(feature = "opentelemetry")]
+    pub fn set_otlp_endpoint(&mut self, endpoint: impl Into<String>) -> &mut Self {
+        self.otlp_endpoint = Some(endpoint.into());
+        self
+    }
+
+    // Histogram buckets are registered in start_application, so histograms have to be written after it
+    pub fn configure_metrics(&mut self, config: impl Fn(&mut SdkMetricsConfig)) -> &mut Self {
+        config(&mut self.metrics_config);
+        self
+    }
+
     pub async fn start_application(&mut self) {
+        self.metrics_config
+            .apply(self.app_name.as_str(), self.app_version.as_str())
+            .unwrap_or_else(|err| panic!("Invalid metrics configuration. {}", err));
+
+        let service_version = Arc::new(ServiceVersion::new(
+            self.app_name.as_str(),
+            self.app_version.as_str(),
+            &self.build_info,
+        ));
+        service_version.write_metric();
+
+        self.http_server_builder
+            .set_version_middleware(VersionMiddleware::new(service_version.clone()));
+
+        if let Some(admin_server_builder) = self.admin_server_builder.as_mut() {
+            admin_server_builder
+                .register_custom_middleware(Arc::new(VersionMiddleware::new(service_version)));
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        self.start_tracing();
+
+        #[cfg(feature = "runtime-metrics")]
+        crate::warn_if_runtime_metrics_are_missing();
+
+        crate::spawn_shutdown_signal_handler(
+            self.app_states.clone(),
+            self.readiness_probe.clone(),
+            self.shutdown_pre_drain_delay,
+        );
+
         self.app_states.set_initialized();
         for timer in self.background_timers.iter() {
             timer.start(self.app_states.clone(), my_logger::LOGGER.clone());
@@ -126,23 +368,126 @@ impl ServiceContext {
         #[cfg(feature = "my-service-bus")]
         self.sb_client.start().await;
 
-        let mut http_server = self.http_server_builder.build();
-
-        if std::env::var("HTTP2").is_ok() {
-            http_server.start_h2(self.app_states.clone(), my_logger::LOGGER.clone());
-        } else {
-            http_server.start(self.app_states.clone(), my_logger::LOGGER.clone());
-        }
+        let http_server = self
+            .http_server_builder
+            .start(self.app_states.clone())
+            .await
+            .unwrap_or_else(|err| panic!("Can not start HTTP server. {}", err));
 
         self.http_server = Some(http_server);
 
+        if let Some(mut admin_server_builder) = self.admin_server_builder.take() {
+            self.admin_server = Some(admin_server_builder.start(self.app_states.clone()));
+        }
+
         #[cfg(feature = "grpc")]
         if let Some(mut grpc_server_builder) = self.grpc_server_builder.take() {
-            self.grpc_server = Some(grpc_server_builder.build());
+            let grpc_server = grpc_server_builder
+                .build(
+                    self.app_states.clone(),
+                    self.readiness_probe.clone(),
+                    self.health_check_registry.clone(),
+                )
+                .unwrap_or_else(|err| panic!("Can not start GRPC server. {}", err));
+
+            self.grpc_server = Some(grpc_server);
         }
 
+        self.start_health_check_metrics_refresh();
+
         println!("Application is stated");
         self.app_states.wait_until_shutdown().await;
+
+        self.shutdown().await;
+    }
+
+    // Keeps service_health_check_status gauges fresh even if nobody requests /api/health
+    fn start_health_check_metrics_refresh(&self) {
+        let app_states = self.app_states.clone();
+        let health_check_registry = self.health_check_registry.clone();
+
+        tokio::spawn(async move {
+            while !app_states.is_shutting_down() {
+                health_check_registry.get_report().await;
+                tokio::time::sleep(HEALTH_CHECK_METRICS_REFRESH_INTERVAL).await;
+            }
+        });
+    }
+
+    // Service keeps working without traces if exporter can not be created
+    #[cfg(feature = "opentelemetry")]
+    fn start_tracing(&mut self) {
+        let Some(otlp_endpoint) = crate::get_otlp_endpoint(self.otlp_endpoint.as_deref()) else {
+            return;
+        };
+
+        match crate::start_otel_tracing(
+            self.app_name.as_str(),
+            self.app_version.as_str(),
+            &otlp_endpoint,
+        ) {
+            Ok(tracer_provider) => {
+                println!("Traces are exported to {}", otlp_endpoint);
+                self.tracer_provider = Some(tracer_provider);
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Start tracing".to_string(),
+                    err,
+                    my_logger::LogEventCtx::new(),
+                );
+            }
+        }
+    }
+
+    // Teardown runs in reverse start order. New requests are rejected as soon as AppStates
+    // is shutting down, so here we only wait for in-flight ones within a single drain deadline
+    async fn shutdown(&mut self) {
+        println!("Application is shutting down");
+
+        let drain_deadline = std::time::Instant::now() + self.shutdown_drain_timeout;
+
+        #[cfg(feature = "grpc")]
+        if let Some(grpc_server) = self.grpc_server.as_mut() {
+            let timeout = drain_deadline.saturating_duration_since(std::time::Instant::now());
+            if !grpc_server.wait_until_stopped(timeout).await {
+                println!(
+                    "GRPC server did not drain in {:?}",
+                    self.shutdown_drain_timeout
+                );
+            }
+        }
+
+        let timeout = drain_deadline.saturating_duration_since(std::time::Instant::now());
+        if !self.in_flight_requests.wait_until_drained(timeout).await {
+            println!(
+                "HTTP server did not drain in {:?}. {} requests are still in flight",
+                self.shutdown_drain_timeout,
+                self.in_flight_requests.get_count()
+            );
+        }
+
+        self.http_server = None;
+        self.admin_server = None;
+
+        for timer in self.background_timers.iter() {
+            timer.stop();
+        }
+
+        #[cfg(feature = "my-service-bus")]
+        self.sb_client.stop().await;
+        #[cfg(feature = "my-nosql-data-reader-sdk")]
+        self.my_no_sql_connection.stop().await;
+
+        // Flushes spans which are still in the batch
+        #[cfg(feature = "opentelemetry")]
+        if let Some(tracer_provider) = self.tracer_provider.take() {
+            if let Err(err) = tracer_provider.shutdown() {
+                println!("Can not flush traces. {}", err);
+            }
+        }
+
+        println!("Application is stopped");
     }
 
     //ns
@@ -154,7 +499,17 @@ impl ServiceContext {
     ) -> Arc<my_no_sql_sdk::reader::MyNoSqlDataReaderTcp<TMyNoSqlEntity>> {
         use my_no_sql_sdk::abstractions::MyNoSqlEntitySerializer;
 
-        let reader = self.my_no_sql_connection.get_reader().await;
+        let reader: Arc<my_no_sql_sdk::reader::MyNoSqlDataReaderTcp<TMyNoSqlEntity>> =
+            self.my_no_sql_connection.get_reader().await;
+
+        self.no_sql_readiness_check.reader_registered();
+        let no_sql_readiness_check = self.no_sql_readiness_check.clone();
+        let reader_to_wait = reader.clone();
+        tokio::spawn(async move {
+            reader_to_wait.wait_until_first_data_arrives().await;
+            no_sql_readiness_check.reader_received_snapshot();
+        });
+
         return reader;
     }
 
@@ -167,6 +522,12 @@ impl ServiceContext {
         callback: Arc<dyn SubscriberCallback<TModel> + Send + Sync + 'static>,
         queue_type: TopicQueueType,
     ) -> &Self {
+        #[cfg(feature = "opentelemetry")]
+        let callback = Arc::new(crate::SbTracingCallback::new(
+            self.app_name.as_str().to_string(),
+            callback,
+        ));
+
         self.sb_client
             .subscribe(self.app_name.clone(), queue_type, callback)
             .await;
@@ -184,29 +545,47 @@ impl ServiceContext {
         suffix: impl Into<StrOrString<'static>>,
     ) -> &Self {
         let suffix: StrOrString<'static> = suffix.into();
+        let queue_id = format!("{}{}", self.app_name.as_str(), suffix.as_str());
+
+        #[cfg(feature = "opentelemetry")]
+        let callback = Arc::new(crate::SbTracingCallback::new(queue_id.clone(), callback));
+
         self.sb_client
-            .subscribe(
-                format!("{}{}", self.app_name.as_str(), suffix.as_str()),
-                queue_type,
-                callback,
-            )
+            .subscribe(queue_id, queue_type, callback)
             .await;
 
         self
     }
 
-    #[cfg(feature = "my-service-bus")]
+    #[cfg(all(feature = "my-service-bus", not(feature = "opentelemetry")))]
     pub async fn get_sb_publisher<TModel: MySbMessageSerializer + GetMySbModelTopicId>(
         &self,
         do_retries: bool,
     ) -> MyServiceBusPublisher<TModel> {
-        return self.sb_client.get_publisher(do_retries).await;
+        self.sb_client.get_publisher(do_retries).await
+    }
+
+    // Adds trace context of the current span to the headers of published messages
+    #[cfg(all(feature = "my-service-bus", feature = "opentelemetry"))]
+    pub async fn get_sb_publisher<TModel: MySbMessageSerializer + GetMySbModelTopicId>(
+        &self,
+        do_retries: bool,
+    ) -> SbPublisher<TModel> {
+        SbPublisher::new(self.sb_client.get_publisher(do_retries).await)
     }
 
     #[cfg(feature = "grpc")]
     pub fn configure_grpc_server(&mut self, config: impl Fn(&mut GrpcServerBuilder)) {
-        let mut grpc_server_builder = GrpcServerBuilder::new();
-        config(&mut grpc_server_builder);
-        self.grpc_server_builder = Some(grpc_server_builder);
+        let grpc_server_builder = self
+            .grpc_server_builder
+            .get_or_insert_with(GrpcServerBuilder::new);
+        config(grpc_server_builder);
     }
 }
+
+// Log level and RequestId of the current request are applied to the sink
+fn plug_log_sink(sink: Arc<dyn MyLoggerReader + Send + Sync + 'static>) {
+    my_logger::LOGGER.plug_reader(Arc::new(LogLevelFilterReader::new(Arc::new(
+        RequestIdLogReader::new(sink),
+    ))));
+}
@@ -0,0 +1,9 @@
+mod tls_certificate_source;
+mod reloadable_cert_resolver;
+mod reloadable_client_verifier;
+mod tls_config;
+
+pub use tls_certificate_source::*;
+pub use reloadable_cert_resolver::*;
+pub use reloadable_client_verifier::*;
+pub use tls_config::*;
@@ -0,0 +1,173 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use my_logger::LogEventCtx;
+use rustls::{
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+
+use crate::TlsCertificateSource;
+
+pub(crate) const CERTIFICATE_RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub struct ReloadableCertResolver {
+    source: TlsCertificateSource,
+    current: RwLock<Arc<CertifiedKey>>,
+    last_modified: RwLock<Option<(SystemTime, SystemTime)>>,
+}
+
+impl ReloadableCertResolver {
+    pub fn new(source: TlsCertificateSource) -> Result<Arc<Self>, String> {
+        let last_modified = source.get_last_modified();
+        let certified_key = source.load_certified_key()?;
+
+        Ok(Arc::new(Self {
+            source,
+            current: RwLock::new(Arc::new(certified_key)),
+            last_modified: RwLock::new(last_modified),
+        }))
+    }
+
+    // Polls file modification time, since mounted secrets are usually replaced by symlink swap
+    // which file system notifications do not always report
+    pub fn start_watching(self: &Arc<Self>) {
+        if self.source.get_last_modified().is_none() {
+            return;
+        }
+
+        let resolver = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(CERTIFICATE_RELOAD_CHECK_INTERVAL).await;
+
+                let Some(resolver) = resolver.upgrade() else {
+                    return;
+                };
+
+                resolver.reload_if_changed();
+            }
+        });
+    }
+
+    // Returns true if the files were changed and the certificate is loaded
+    pub fn reload_if_changed(&self) -> bool {
+        let Some(modified) = self.source.get_last_modified() else {
+            return false;
+        };
+
+        if *self.last_modified.read().unwrap() == Some(modified) {
+            return false;
+        }
+
+        match self.source.load_certified_key() {
+            Ok(certified_key) => {
+                *self.current.write().unwrap() = Arc::new(certified_key);
+                *self.last_modified.write().unwrap() = Some(modified);
+
+                my_logger::LOGGER.write_info(
+                    "TLS".to_string(),
+                    format!("Certificate is reloaded from {:?}", self.source),
+                    LogEventCtx::new(),
+                );
+
+                true
+            }
+            Err(err) => {
+                // Files may be caught in the middle of update. Keep the old certificate and retry
+                my_logger::LOGGER.write_error(
+                    "TLS".to_string(),
+                    format!("Can not reload certificate: {}", err),
+                    LogEventCtx::new(),
+                );
+
+                false
+            }
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadableCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().unwrap().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::ReloadableCertResolver;
+    use crate::TlsCertificateSource;
+
+    const CERT1: &str = include_str!("test_data/cert1.pem");
+    const KEY1: &str = include_str!("test_data/cert1.key.pem");
+    const CERT2: &str = include_str!("test_data/cert2.pem");
+    const KEY2: &str = include_str!("test_data/cert2.key.pem");
+
+    // Modification time is set explicitly, since file systems may have coarse timestamps
+    fn write_file(path: &std::path::Path, content: &str, modified: SystemTime) {
+        std::fs::write(path, content).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn get_current_cert(resolver: &ReloadableCertResolver) -> Vec<u8> {
+        resolver.current.read().unwrap().cert[0].to_vec()
+    }
+
+    #[test]
+    fn test_reload_when_files_changed() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        let dir = std::env::temp_dir().join(format!("sdk-tls-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+
+        let first_modified = SystemTime::now() - Duration::from_secs(60);
+        write_file(&cert_path, CERT1, first_modified);
+        write_file(&key_path, KEY1, first_modified);
+
+        let resolver = ReloadableCertResolver::new(TlsCertificateSource::from_files(
+            cert_path.to_str().unwrap(),
+            key_path.to_str().unwrap(),
+        ))
+        .unwrap();
+
+        let first_cert = get_current_cert(&resolver);
+        assert!(!resolver.reload_if_changed());
+
+        // Key is not written yet: the old certificate is kept
+        let second_modified = SystemTime::now();
+        write_file(&key_path, "broken", second_modified);
+        assert!(!resolver.reload_if_changed());
+        assert_eq!(get_current_cert(&resolver), first_cert);
+
+        write_file(&cert_path, CERT2, second_modified);
+        write_file(&key_path, KEY2, second_modified);
+        assert!(resolver.reload_if_changed());
+        assert_ne!(get_current_cert(&resolver), first_cert);
+        assert!(!resolver.reload_if_changed());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_pem_source_is_not_reloaded() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        let resolver =
+            ReloadableCertResolver::new(TlsCertificateSource::from_pem(CERT1, KEY1)).unwrap();
+
+        assert!(!resolver.reload_if_changed());
+    }
+}
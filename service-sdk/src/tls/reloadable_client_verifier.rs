@@ -0,0 +1,244 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use my_logger::LogEventCtx;
+use rustls::{
+    client::danger::HandshakeSignatureValid,
+    pki_types::{CertificateDer, UnixTime},
+    server::{
+        danger::{ClientCertVerified, ClientCertVerifier},
+        WebPkiClientVerifier,
+    },
+    DigitallySignedStruct, DistinguishedName, RootCertStore, SignatureScheme,
+};
+
+use super::{
+    reloadable_cert_resolver::CERTIFICATE_RELOAD_CHECK_INTERVAL,
+    tls_certificate_source::get_file_modified,
+};
+use crate::load_certificates_from_file;
+
+// Verifies client certificates against the CA file and picks up the file changes,
+// so rotated client CA does not require restart
+#[derive(Debug)]
+pub struct ReloadableClientVerifier {
+    client_ca_path: String,
+    current: RwLock<Arc<dyn ClientCertVerifier>>,
+    last_modified: RwLock<Option<SystemTime>>,
+}
+
+impl ReloadableClientVerifier {
+    pub fn new(client_ca_path: impl Into<String>) -> Result<Arc<Self>, String> {
+        let client_ca_path = client_ca_path.into();
+        let last_modified = get_file_modified(&client_ca_path);
+        let verifier = build_verifier(&client_ca_path)?;
+
+        Ok(Arc::new(Self {
+            client_ca_path,
+            current: RwLock::new(verifier),
+            last_modified: RwLock::new(last_modified),
+        }))
+    }
+
+    pub fn start_watching(self: &Arc<Self>) {
+        let verifier = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(CERTIFICATE_RELOAD_CHECK_INTERVAL).await;
+
+                let Some(verifier) = verifier.upgrade() else {
+                    return;
+                };
+
+                verifier.reload_if_changed();
+            }
+        });
+    }
+
+    // Returns true if the CA file was changed and loaded
+    pub fn reload_if_changed(&self) -> bool {
+        let Some(modified) = get_file_modified(&self.client_ca_path) else {
+            return false;
+        };
+
+        if *self.last_modified.read().unwrap() == Some(modified) {
+            return false;
+        }
+
+        match build_verifier(&self.client_ca_path) {
+            Ok(verifier) => {
+                *self.current.write().unwrap() = verifier;
+                *self.last_modified.write().unwrap() = Some(modified);
+
+                my_logger::LOGGER.write_info(
+                    "TLS".to_string(),
+                    format!("Client CA is reloaded from {}", self.client_ca_path),
+                    LogEventCtx::new(),
+                );
+
+                true
+            }
+            Err(err) => {
+                // File may be caught in the middle of update. Keep the old CA and retry
+                my_logger::LOGGER.write_error(
+                    "TLS".to_string(),
+                    format!("Can not reload client CA: {}", err),
+                    LogEventCtx::new(),
+                );
+
+                false
+            }
+        }
+    }
+
+    fn get_current(&self) -> Arc<dyn ClientCertVerifier> {
+        self.current.read().unwrap().clone()
+    }
+}
+
+fn build_verifier(client_ca_path: &str) -> Result<Arc<dyn ClientCertVerifier>, String> {
+    let mut roots = RootCertStore::empty();
+
+    for cert in load_certificates_from_file(client_ca_path)? {
+        roots
+            .add(cert)
+            .map_err(|err| format!("Invalid client CA certificate: {}", err))?;
+    }
+
+    WebPkiClientVerifier::builder(Arc::new(roots))
+        .build()
+        .map_err(|err| format!("Can not build client certificate verifier: {}", err))
+}
+
+impl ClientCertVerifier for ReloadableClientVerifier {
+    // Hints are borrowed from the verifier which can be replaced at any time, so they are not sent.
+    // They are optional: clients with a single certificate present it anyway
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        self.get_current()
+            .verify_client_cert(end_entity, intermediates, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.get_current()
+            .verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.get_current()
+            .verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.get_current().supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use rustls::{
+        pki_types::CertificateDer, pki_types::UnixTime, server::danger::ClientCertVerifier,
+    };
+
+    use super::ReloadableClientVerifier;
+    use crate::load_certificates_from_file;
+
+    const OTHER_CA: &str = include_str!("test_data/cert1.pem");
+    const CLIENT_CA: &str = include_str!("test_data/client_ca.pem");
+    // Signed by client_ca.pem
+    const CLIENT_CERT: &str = include_str!("test_data/client.pem");
+
+    fn write_file(path: &std::path::Path, content: &str, modified: SystemTime) {
+        std::fs::write(path, content).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn verify(verifier: &ReloadableClientVerifier, cert: &CertificateDer<'static>) -> bool {
+        verifier
+            .verify_client_cert(cert, &[], UnixTime::now())
+            .is_ok()
+    }
+
+    #[test]
+    fn test_reload_when_ca_changed() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        let dir = std::env::temp_dir().join(format!("sdk-tls-client-ca-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ca_path = dir.join("ca.pem");
+        let ca_path_str = ca_path.to_str().unwrap();
+
+        write_file(
+            &ca_path,
+            CLIENT_CERT,
+            SystemTime::now() - Duration::from_secs(60),
+        );
+        let client_cert = load_certificates_from_file(ca_path_str).unwrap().remove(0);
+
+        write_file(
+            &ca_path,
+            OTHER_CA,
+            SystemTime::now() - Duration::from_secs(60),
+        );
+        let verifier = ReloadableClientVerifier::new(ca_path_str).unwrap();
+
+        assert!(!verify(&verifier, &client_cert));
+        assert!(!verifier.reload_if_changed());
+
+        write_file(&ca_path, CLIENT_CA, SystemTime::now());
+        assert!(verifier.reload_if_changed());
+        assert!(verify(&verifier, &client_cert));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_broken_ca_keeps_previous() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        let dir = std::env::temp_dir().join(format!("sdk-tls-broken-ca-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ca_path = dir.join("ca.pem");
+
+        write_file(
+            &ca_path,
+            CLIENT_CA,
+            SystemTime::now() - Duration::from_secs(60),
+        );
+        let verifier = ReloadableClientVerifier::new(ca_path.to_str().unwrap()).unwrap();
+
+        write_file(&ca_path, "", SystemTime::now());
+        assert!(!verifier.reload_if_changed());
+        assert!(!verifier.supported_verify_schemes().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
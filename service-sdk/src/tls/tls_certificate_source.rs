@@ -0,0 +1,146 @@
+use std::{io::BufReader, time::SystemTime};
+
+use rustls::{
+    crypto::CryptoProvider,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    sign::CertifiedKey,
+};
+
+#[derive(Debug, Clone)]
+pub enum TlsCertificateSource {
+    // Files are watched and certificate is reloaded when they are changed
+    Files { cert_path: String, key_path: String },
+    // PEM content, for instance taken from settings
+    Pem { cert: String, key: String },
+}
+
+impl TlsCertificateSource {
+    pub fn from_files(cert_path: impl Into<String>, key_path: impl Into<String>) -> Self {
+        Self::Files {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+        }
+    }
+
+    pub fn from_pem(cert: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::Pem {
+            cert: cert.into(),
+            key: key.into(),
+        }
+    }
+
+    pub fn load_certified_key(&self) -> Result<CertifiedKey, String> {
+        let (cert, key) = match self {
+            Self::Files {
+                cert_path,
+                key_path,
+            } => (read_file(cert_path)?, read_file(key_path)?),
+            Self::Pem { cert, key } => (cert.as_bytes().to_vec(), key.as_bytes().to_vec()),
+        };
+
+        let certs = parse_certificates(&cert)?;
+        let key = parse_private_key(&key)?;
+
+        let provider = CryptoProvider::get_default()
+            .ok_or_else(|| "Rustls crypto provider is not installed".to_string())?;
+
+        let signing_key = provider
+            .key_provider
+            .load_private_key(key)
+            .map_err(|err| format!("Unsupported private key: {}", err))?;
+
+        Ok(CertifiedKey::new(certs, signing_key))
+    }
+
+    // None for the sources which can not change
+    pub fn get_last_modified(&self) -> Option<(SystemTime, SystemTime)> {
+        match self {
+            Self::Files {
+                cert_path,
+                key_path,
+            } => Some((get_file_modified(cert_path)?, get_file_modified(key_path)?)),
+            Self::Pem { .. } => None,
+        }
+    }
+}
+
+pub fn load_certificates_from_file(path: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    parse_certificates(&read_file(path)?)
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("Can not read {}: {}", path, err))
+}
+
+pub(crate) fn get_file_modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).ok()?.modified().ok()
+}
+
+fn parse_certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(pem))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Invalid certificate PEM: {}", err))?;
+
+    if certs.is_empty() {
+        return Err("No certificates found in PEM".to_string());
+    }
+
+    Ok(certs)
+}
+
+fn parse_private_key(pem: &[u8]) -> Result<PrivateKeyDer<'static>, String> {
+    rustls_pemfile::private_key(&mut BufReader::new(pem))
+        .map_err(|err| format!("Invalid private key PEM: {}", err))?
+        .ok_or_else(|| "No private key found in PEM".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_certificates, parse_private_key, TlsCertificateSource};
+
+    const CERT: &str = include_str!("test_data/cert1.pem");
+    const KEY: &str = include_str!("test_data/cert1.key.pem");
+
+    #[test]
+    fn test_parse_certificates() {
+        let certs = parse_certificates(CERT.as_bytes()).unwrap();
+        assert_eq!(certs.len(), 1);
+
+        let chain = format!("{}{}", CERT, include_str!("test_data/cert2.pem"));
+        let certs = parse_certificates(chain.as_bytes()).unwrap();
+        assert_eq!(certs.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_certificates_without_certificate() {
+        assert!(parse_certificates(b"").is_err());
+        assert!(parse_certificates(KEY.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_private_key() {
+        assert!(parse_private_key(KEY.as_bytes()).is_ok());
+        assert!(parse_private_key(CERT.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_load_certified_key_from_pem() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        let source = TlsCertificateSource::from_pem(CERT, KEY);
+
+        let certified_key = source.load_certified_key().unwrap();
+
+        assert_eq!(certified_key.cert.len(), 1);
+        assert!(source.get_last_modified().is_none());
+    }
+
+    #[test]
+    fn test_load_certified_key_from_missing_files() {
+        let source = TlsCertificateSource::from_files("/not/existing.pem", "/not/existing.key");
+
+        let err = source.load_certified_key().unwrap_err();
+
+        assert!(err.contains("/not/existing.pem"));
+    }
+}
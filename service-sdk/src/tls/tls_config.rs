@@ -0,0 +1,43 @@
+use rustls::ServerConfig;
+
+use crate::{ReloadableCertResolver, ReloadableClientVerifier, TlsCertificateSource};
+
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub certificate: TlsCertificateSource,
+    // When set, clients have to present a certificate signed by one of these CAs.
+    // The file is watched and reloaded the same way as the certificate files
+    pub client_ca_path: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn new(certificate: TlsCertificateSource) -> Self {
+        Self {
+            certificate,
+            client_ca_path: None,
+        }
+    }
+
+    pub fn build_server_config(&self, alpn_protocols: &[&[u8]]) -> Result<ServerConfig, String> {
+        let resolver = ReloadableCertResolver::new(self.certificate.clone())?;
+        resolver.start_watching();
+
+        let builder = ServerConfig::builder();
+
+        let mut server_config = match self.client_ca_path.as_ref() {
+            Some(client_ca_path) => {
+                let verifier = ReloadableClientVerifier::new(client_ca_path.as_str())?;
+                verifier.start_watching();
+
+                builder
+                    .with_client_cert_verifier(verifier)
+                    .with_cert_resolver(resolver)
+            }
+            None => builder.with_no_client_auth().with_cert_resolver(resolver),
+        };
+
+        server_config.alpn_protocols = alpn_protocols.iter().map(|itm| itm.to_vec()).collect();
+
+        Ok(server_config)
+    }
+}